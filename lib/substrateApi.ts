@@ -1,7 +1,28 @@
 // Substrate RPC client integration using polkadot-js API
 import { ApiPromise, WsProvider } from '@polkadot/api';
+import type { Signer, SignerResult } from '@polkadot/api/types';
+import type { SignerPayloadRaw } from '@polkadot/types/types';
+import { hexToU8a, u8aConcat, u8aToHex } from '@polkadot/util';
+import { blake2AsU8a } from '@polkadot/util-crypto';
+import { ml_dsa65 } from '@noble/post-quantum/ml-dsa';
 let apiPromise: ApiPromise | null = null;
 
+/**
+ * Types of the runtime's post-quantum transactions: accounts are the BLAKE3 hash
+ * of an ML-DSA-65 public key, and the key travels with each signature.
+ */
+const types = {
+  Address: 'AccountId',
+  LookupSource: 'AccountId',
+  PQPublic: '[u8; 1952]',
+  PQSignature: {
+    _enum: {
+      MlDsa65: { public: 'PQPublic', signature: '[u8; 3309]' },
+    },
+  },
+  ExtrinsicSignature: 'PQSignature',
+};
+
 /**
  * Connect to a Substrate node via WebSocket endpoint (default ws://localhost:9944)
  */
@@ -10,28 +31,81 @@ export async function getApi(endpoint = 'ws://localhost:9944'): Promise<ApiPromi
     return apiPromise;
   }
   const provider = new WsProvider(endpoint);
-  apiPromise = await ApiPromise.create({ provider });
+  // Blocks are hashed with BLAKE3
+  const { hash } = await import('@c4312/blake3-wasm');
+  apiPromise = await ApiPromise.create({
+    provider,
+    types,
+    hasher: (data) => new Uint8Array(hash(data)),
+  });
   await apiPromise.isReady;
   return apiPromise;
 }
+
+/** An ML-DSA-65 key pair and the account it controls. */
+export interface PQAccount {
+  publicKey: Uint8Array;
+  secretKey: Uint8Array;
+  /** Hex-encoded BLAKE3 hash of the public key */
+  address: string;
+}
+
+/**
+ * Derive the ML-DSA-65 account of a 32-byte seed, the same key the runtime's
+ * `ExpandedSigningKey::from_seed` gives.
+ */
+export async function pqAccount(seed: Uint8Array): Promise<PQAccount> {
+  const { publicKey, secretKey } = ml_dsa65.keygen(seed);
+  const { hash } = await import('@c4312/blake3-wasm');
+  return { publicKey, secretKey, address: u8aToHex(new Uint8Array(hash(publicKey))) };
+}
+
+/**
+ * Development account of seed `0x01..01`; start the node with
+ * `--endow 0x177c577d91cf59f1008512a5960e280ffc1889d74e10966fa6d6b9d12fbecc3c`
+ * to fund it.
+ */
+export function devAccount(): Promise<PQAccount> {
+  return pqAccount(new Uint8Array(32).fill(1));
+}
+
+/**
+ * Signs extrinsic payloads with `account`, returning the SCALE-encoded
+ * `PQSignature`. Like the runtime, payloads over 256 bytes are signed by their
+ * BLAKE2-256 hash.
+ */
+export function pqSigner(account: PQAccount): Signer {
+  let id = 0;
+  return {
+    async signRaw({ data }: SignerPayloadRaw): Promise<SignerResult> {
+      let payload = hexToU8a(data);
+      if (payload.length > 256) {
+        payload = blake2AsU8a(payload, 256);
+      }
+      const signature = ml_dsa65.sign(payload, account.secretKey);
+      // `PQSignature::MlDsa65` is variant 0
+      const encoded = u8aConcat(new Uint8Array([0]), account.publicKey, signature);
+      return { id: ++id, signature: u8aToHex(encoded) };
+    },
+  };
+}
+
 /**
- * Submit a balance transfer from a sender (default Alice) to a recipient.
+ * Submit a balance transfer from a sender (default `devAccount()`) to a recipient.
  * Returns the transaction hash of the in-block or finalized inclusion.
  */
 export async function transfer(
   to: string,
   amount: number | string | bigint,
   tip: number | string | bigint = 0,
-  sender?: import('@polkadot/keyring').KeyringPair
+  sender?: PQAccount
 ): Promise<string> {
   const api = await getApi();
-  // Use default dev account Alice if no sender provided
-  const keyring = new (await import('@polkadot/keyring')).Keyring({ type: 'sr25519' });
-  const signer = sender || keyring.addFromUri('//Alice');
+  const account = sender || (await devAccount());
   return new Promise<string>((resolve, reject) => {
     api.tx.balances
-      .transfer(to, amount)
-      .signAndSend(signer, { tip }, ({ status, dispatchError }) => {
+      .transferAllowDeath(to, amount)
+      .signAndSend(account.address, { tip, signer: pqSigner(account) }, ({ status, dispatchError }) => {
         if (dispatchError) {
           reject(dispatchError.toString());
         } else if (status.isInBlock || status.isFinalized) {
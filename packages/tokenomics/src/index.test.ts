@@ -0,0 +1,67 @@
+import { describe, test, expect } from 'vitest';
+import { Tokenomics } from '@coin/tokenomics';
+import rewards from '../vectors/rewards.json';
+import fees from '../vectors/fees.json';
+import defaults from '../vectors/defaults.json';
+
+// The same vectors are checked against the Rust crate (substrate-node/tokenomics/tests)
+describe('Reward schedule golden vectors', () => {
+  rewards.schedules.forEach((schedule) => {
+    const tokenomics = new Tokenomics({
+      initialReward: BigInt(schedule.initialReward),
+      rewardHalvingInterval: Number(schedule.halvingInterval),
+      tailEmission: BigInt(schedule.tailEmission),
+      maxSupply: BigInt(schedule.maxSupply)
+    });
+
+    schedule.blockRewards.forEach(({ height, minted, reward }) => {
+      test(`${schedule.name}: reward at height ${height} after ${minted}`, () => {
+        expect(tokenomics.getBlockReward(BigInt(height), BigInt(minted))).toBe(BigInt(reward));
+      });
+    });
+
+    schedule.supply.forEach(({ height, supply }) => {
+      test(`${schedule.name}: supply at height ${height}`, () => {
+        expect(tokenomics.getSupplyAt(BigInt(height))).toBe(BigInt(supply));
+      });
+    });
+  });
+});
+
+describe('Fee golden vectors', () => {
+  fees.fees.forEach(({ amount, basisPoints, fee }) => {
+    test(`${amount} at ${basisPoints}bp`, () => {
+      const tokenomics = new Tokenomics({ feeBasisPoints: basisPoints });
+      expect(tokenomics.calculateFee(BigInt(amount))).toBe(BigInt(fee));
+    });
+  });
+});
+
+// Without a config, the runtime's constants apply
+describe('Default golden vectors', () => {
+  const tokenomics = new Tokenomics();
+  const runtime = rewards.schedules.find((schedule) => schedule.name === defaults.schedule)!;
+
+  test('defaults match the runtime', () => {
+    expect(tokenomics.getConfig()).toEqual({
+      targetBlockTime: Number(defaults.targetBlockTimeMs) / 1000,
+      initialReward: BigInt(defaults.initialReward),
+      rewardHalvingInterval: Number(defaults.halvingInterval),
+      tailEmission: BigInt(defaults.tailEmission),
+      maxSupply: BigInt(defaults.maxSupply),
+      feeBasisPoints: defaults.feeBasisPoints
+    });
+  });
+
+  runtime.blockRewards.forEach(({ height, minted, reward }) => {
+    test(`default reward at height ${height} after ${minted}`, () => {
+      expect(tokenomics.getBlockReward(BigInt(height), BigInt(minted))).toBe(BigInt(reward));
+    });
+  });
+
+  runtime.supply.forEach(({ height, supply }) => {
+    test(`default supply at height ${height}`, () => {
+      expect(tokenomics.getSupplyAt(BigInt(height))).toBe(BigInt(supply));
+    });
+  });
+});
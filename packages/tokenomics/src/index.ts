@@ -1,30 +1,56 @@
+import { RewardSchedule, fee, feeBasisPoints, targetBlockTimeMs } from "coin-tokenomics";
+
+// Reward and fee rules come from the `coin-tokenomics` Rust crate (built to wasm),
+// so they match the runtime's to the unit
 export interface TokenomicsConfig {
   targetBlockTime: number;    // seconds
   initialReward: bigint;
   rewardHalvingInterval: number;  // blocks
-  feeRate: number;           // fraction (e.g., 0.0001 for 0.01%)
+  tailEmission: bigint;      // least reward per block
+  maxSupply: bigint;         // most that block rewards add up to
+  feeBasisPoints: number;    // hundredths of a percent (e.g., 1 for 0.01%)
 }
 
 export class Tokenomics {
   private config: TokenomicsConfig;
+  private schedule: RewardSchedule;
 
+  // Defaults are the runtime's, as exported by `coin-tokenomics`
   constructor(config?: Partial<TokenomicsConfig>) {
+    const chain = RewardSchedule.chain();
     this.config = {
-      targetBlockTime: 12,
-      initialReward: BigInt(888888),
-      rewardHalvingInterval: 200000,
-      feeRate: 0.0001,
+      targetBlockTime: Number(targetBlockTimeMs()) / 1000,
+      initialReward: chain.initialReward,
+      rewardHalvingInterval: Number(chain.halvingInterval),
+      tailEmission: chain.tailEmission,
+      maxSupply: chain.maxSupply,
+      feeBasisPoints: feeBasisPoints(),
       ...config
     };
+    chain.free();
+    this.schedule = new RewardSchedule(
+      this.config.initialReward,
+      BigInt(this.config.rewardHalvingInterval),
+      this.config.tailEmission,
+      this.config.maxSupply
+    );
+  }
+
+  getConfig(): TokenomicsConfig {
+    return { ...this.config };
+  }
+
+  // Reward of the block at `height`, once earlier blocks were paid `minted`
+  getBlockReward(height: number | bigint, minted: bigint = BigInt(0)): bigint {
+    return this.schedule.blockReward(BigInt(height), minted);
   }
 
-  getBlockReward(height: number): bigint {
-    const halvings = Math.floor(height / this.config.rewardHalvingInterval);
-    return this.config.initialReward >> BigInt(halvings);
+  // Total paid to the blocks up to and including `height`
+  getSupplyAt(height: number | bigint): bigint {
+    return this.schedule.supplyAt(BigInt(height));
   }
 
   calculateFee(amount: bigint): bigint {
-    // Compute fee = amount * feeRate
-    return (amount * BigInt(Math.floor(this.config.feeRate * 1e8))) / BigInt(1e8);
+    return fee(amount, this.config.feeBasisPoints);
   }
 }
@@ -0,0 +1,203 @@
+//! Difficulty simulator: mines scripted hashrate scenarios on a mock chain that runs
+//! the mining pallet's real retarget code, for tuning the retarget parameters.
+//!
+//! Solve times are drawn from the exponential distribution a memoryless search
+//! produces, with the mean the current difficulty and hashrate give. Block stamps
+//! follow the runtime's rules: every stamp must exceed both the median time of recent
+//! blocks and, as `pallet_timestamp` demands, its parent's stamp. Honest miners stamp
+//! their clock, raised to the earliest valid time if needed.
+
+pub mod mock;
+pub mod scenario;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+
+use mock::{Mining, Params};
+use scenario::{equilibrium_difficulty, Scenario};
+
+/// A simulation run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub params: Params,
+    pub scenario: Scenario,
+    /// Blocks to mine
+    pub blocks: u64,
+    /// Base hashrate, in hashes per second
+    pub hashrate: f64,
+    pub initial_difficulty: u64,
+    /// Seed of the solve-time and miner draws; equal seeds give equal runs
+    pub seed: u64,
+}
+
+/// One mined block.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct BlockRecord {
+    pub block: u64,
+    /// Difficulty the block was mined at
+    pub difficulty: u64,
+    /// Network hashrate while it was mined, in hashes per second
+    pub hashrate: f64,
+    /// Honest time it was found at, in milliseconds since genesis
+    pub time: u64,
+    /// Milliseconds since the previous block was found
+    pub solve_time: u64,
+    /// Time it was stamped with
+    pub timestamp: u64,
+    /// Stamped time since the previous block
+    pub block_time: u64,
+    /// Whether its miner warped the stamp
+    pub warped: bool,
+}
+
+/// Blocks of a run and where it left the difficulty.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Run {
+    pub blocks: Vec<BlockRecord>,
+    /// Difficulty the pallet set for the block after the last one
+    pub final_difficulty: u64,
+}
+
+/// Summary of a run, over the honest solve times.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub blocks: u64,
+    /// Milliseconds
+    pub mean_block_time: f64,
+    /// Square milliseconds
+    pub block_time_variance: f64,
+    pub block_time_stddev: f64,
+    pub final_difficulty: u64,
+    /// Blocks after the last hashrate change until the difficulty settles near the
+    /// final hashrate's equilibrium for good; `None` if it never does
+    pub blocks_to_converge: Option<u64>,
+    /// The same span in milliseconds
+    pub time_to_converge: Option<u64>,
+}
+
+/// Relative distance from the equilibrium difficulty that counts as converged,
+/// unless told otherwise. Periodic retargets over 100 blocks alone scatter by about
+/// 10%.
+pub const DEFAULT_CONVERGENCE_TOLERANCE: f64 = 0.2;
+
+/// Mines `config.blocks` blocks.
+pub fn simulate(config: &Config) -> Run {
+    let mut rng = ChaCha8Rng::seed_from_u64(config.seed);
+    let target_block_time = config.params.target_block_time;
+    let warp_share = config.scenario.warp_share();
+    let mut blocks = Vec::with_capacity(config.blocks as usize);
+
+    let final_difficulty =
+        mock::new_chain(config.params, config.initial_difficulty).execute_with(|| {
+            let mut now = 0.0f64;
+            let (mut previous_time, mut previous_stamp) = (0u64, 0u64);
+            for block in 1..=config.blocks {
+                let difficulty = Mining::current_difficulty();
+                let hashrate = config.scenario.hashrate(
+                    block,
+                    config.blocks,
+                    config.hashrate,
+                    difficulty,
+                    target_block_time,
+                );
+
+                // Memoryless search: exponential with mean difficulty / hashrate
+                let mean = difficulty as f64 / hashrate.max(f64::MIN_POSITIVE) * 1000.0;
+                now += -(1.0 - rng.gen::<f64>()).ln() * mean;
+                let time = now.round() as u64;
+
+                let earliest = pow_primitives::time::earliest_next(&Mining::recent_timestamps())
+                    .max(previous_stamp + 1);
+                let warped = rng.gen::<f64>() < warp_share;
+                let timestamp = if warped { earliest } else { time.max(earliest) };
+                mock::import_block(block, timestamp);
+
+                blocks.push(BlockRecord {
+                    block,
+                    difficulty,
+                    hashrate,
+                    time,
+                    solve_time: time - previous_time,
+                    timestamp,
+                    block_time: timestamp - previous_stamp,
+                    warped,
+                });
+                (previous_time, previous_stamp) = (time, timestamp);
+            }
+            Mining::current_difficulty()
+        });
+
+    Run {
+        blocks,
+        final_difficulty,
+    }
+}
+
+impl Run {
+    /// Summarizes the run of a chain aiming for `target_block_time`, counting the
+    /// difficulty as converged within `tolerance` of the equilibrium.
+    pub fn summary(&self, target_block_time: u64, tolerance: f64) -> Summary {
+        let count = self.blocks.len().max(1) as f64;
+        let mean = self
+            .blocks
+            .iter()
+            .map(|block| block.solve_time as f64)
+            .sum::<f64>()
+            / count;
+        let variance = self
+            .blocks
+            .iter()
+            .map(|block| (block.solve_time as f64 - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        let convergence = self.convergence(target_block_time, tolerance);
+        Summary {
+            blocks: self.blocks.len() as u64,
+            mean_block_time: mean,
+            block_time_variance: variance,
+            block_time_stddev: variance.sqrt(),
+            final_difficulty: self.final_difficulty,
+            blocks_to_converge: convergence.map(|(blocks, _)| blocks),
+            time_to_converge: convergence.map(|(_, time)| time),
+        }
+    }
+
+    /// Blocks and milliseconds from the last hashrate change until the difficulty
+    /// stays near the final hashrate's equilibrium.
+    fn convergence(&self, target_block_time: u64, tolerance: f64) -> Option<(u64, u64)> {
+        let last = self.blocks.last()?;
+        let equilibrium = equilibrium_difficulty(last.hashrate, target_block_time);
+        let settled =
+            |difficulty: u64| (difficulty as f64 - equilibrium).abs() <= tolerance * equilibrium;
+        if !settled(self.final_difficulty) {
+            return None;
+        }
+
+        // Indices of the first block at the final hashrate, and of the first block
+        // from which every difficulty is settled
+        let changed = self
+            .blocks
+            .iter()
+            .rposition(|block| block.hashrate != last.hashrate)
+            .map_or(0, |index| index + 1);
+        let converged = self
+            .blocks
+            .iter()
+            .rposition(|block| !settled(block.difficulty))
+            .map_or(0, |index| index + 1)
+            .max(changed);
+
+        // Mining of block `index` starts when its parent is found
+        let started = |index: usize| {
+            index
+                .checked_sub(1)
+                .map_or(0, |parent| self.blocks[parent].time)
+        };
+        Some((
+            (converged - changed) as u64,
+            started(converged) - started(changed),
+        ))
+    }
+}
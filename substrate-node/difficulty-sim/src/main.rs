@@ -0,0 +1,304 @@
+//! `difficulty-sim`: replay a hashrate scenario through the pallet's difficulty
+//! algorithm and report per-block difficulty and block time.
+//!
+//!     difficulty-sim --scenario step --algorithm lwma --blocks 3000
+//!     difficulty-sim --scenario time-warp --attacker-share 0.4 --format json --output run.json
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use difficulty_sim::{
+    mock::Params, scenario::equilibrium_difficulty, scenario::Scenario, simulate, BlockRecord,
+    Config, Summary, DEFAULT_CONVERGENCE_TOLERANCE,
+};
+use pallet_basic_pallet::difficulty::AlgorithmKind;
+use serde_json::json;
+
+#[derive(Parser)]
+#[command(
+    name = "difficulty-sim",
+    version,
+    about = "Simulate difficulty retargeting under scripted hashrate scenarios"
+)]
+struct Cli {
+    /// Hashrate scenario to mine
+    #[arg(long, value_enum, default_value_t = ScenarioName::Steady)]
+    scenario: ScenarioName,
+    /// Difficulty algorithm
+    #[arg(long, value_enum, default_value_t = Algorithm::Periodic)]
+    algorithm: Algorithm,
+    /// Blocks to mine
+    #[arg(long, default_value_t = 2_000)]
+    blocks: u64,
+    /// Base hashrate, in hashes per second
+    #[arg(long, default_value_t = 100_000.0)]
+    hashrate: f64,
+    /// Starting difficulty (defaults to the base hashrate's equilibrium)
+    #[arg(long)]
+    initial_difficulty: Option<u64>,
+    /// Seed of the random draws
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Target block time, in milliseconds
+    #[arg(long, default_value_t = Params::default().target_block_time)]
+    target_block_time: u64,
+    /// Blocks between periodic retargets
+    #[arg(long, default_value_t = Params::default().period)]
+    period: u64,
+    /// Solve times averaged by LWMA
+    #[arg(long, default_value_t = Params::default().lwma_window)]
+    lwma_window: u32,
+    /// ASERT half-life, in milliseconds
+    #[arg(long, default_value_t = Params::default().asert_half_life)]
+    asert_half_life: u64,
+    /// Blocks whose median time a new block's time must exceed
+    #[arg(long, default_value_t = Params::default().median_time_span)]
+    median_time_span: u32,
+
+    /// `step`: hashrate multiplier during the middle third of the run
+    #[arg(long, default_value_t = 10.0)]
+    step_factor: f64,
+    /// `oscillating`: relative swing of the hashrate
+    #[arg(long, default_value_t = 0.5)]
+    amplitude: f64,
+    /// `oscillating`: blocks per swing
+    #[arg(long, default_value_t = 500)]
+    cycle: u64,
+    /// `hash-and-run`: hashrate of the hopping miners, as a multiple of the base
+    #[arg(long, default_value_t = 4.0)]
+    hopper_factor: f64,
+    /// `hash-and-run`: hoppers mine while the difficulty is below this multiple of
+    /// the base's equilibrium
+    #[arg(long, default_value_t = 1.2)]
+    hopper_threshold: f64,
+    /// `time-warp`: share of blocks mined by the attacker
+    #[arg(long, default_value_t = 0.4)]
+    attacker_share: f64,
+
+    /// Relative distance from the equilibrium difficulty that counts as converged
+    #[arg(long, default_value_t = DEFAULT_CONVERGENCE_TOLERANCE)]
+    convergence_tolerance: f64,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// Write the blocks here instead of stdout
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScenarioName {
+    Steady,
+    Step,
+    Oscillating,
+    HashAndRun,
+    TimeWarp,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Algorithm {
+    Periodic,
+    Lwma,
+    Asert,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// One row per block; the summary goes to stderr
+    Csv,
+    /// Parameters, summary and blocks in one document
+    Json,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let config = cli.config()?;
+    let run = simulate(&config);
+    let summary = run.summary(config.params.target_block_time, cli.convergence_tolerance);
+
+    let mut out: Box<dyn Write> = match &cli.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|err| {
+                format!("cannot create {}: {err}", path.display())
+            })?))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let written = match cli.format {
+        Format::Csv => write_csv(&mut out, &run.blocks).and_then(|()| write_summary(&summary)),
+        Format::Json => {
+            let report = json!({
+                "parameters": {
+                    "scenario": name(cli.scenario),
+                    "algorithm": name(cli.algorithm),
+                    "blocks": config.blocks,
+                    "hashrate": config.hashrate,
+                    "initial_difficulty": config.initial_difficulty,
+                    "seed": config.seed,
+                    "target_block_time": config.params.target_block_time,
+                    "period": config.params.period,
+                    "lwma_window": config.params.lwma_window,
+                    "asert_half_life": config.params.asert_half_life,
+                    "median_time_span": config.params.median_time_span,
+                },
+                "summary": summary,
+                "blocks": run.blocks,
+            });
+            serde_json::to_writer_pretty(&mut out, &report)
+                .map_err(io::Error::from)
+                .and_then(|()| writeln!(out))
+        }
+    };
+    written
+        .and_then(|()| out.flush())
+        .map_err(|err| format!("cannot write output: {err}"))
+}
+
+impl Cli {
+    fn config(&self) -> Result<Config, String> {
+        if self.blocks == 0 {
+            return Err("--blocks must be positive".into());
+        }
+        if !(self.hashrate.is_finite() && self.hashrate > 0.0) {
+            return Err("--hashrate must be positive".into());
+        }
+        if self.target_block_time == 0 {
+            return Err("--target-block-time must be positive".into());
+        }
+        if self.median_time_span == 0 {
+            return Err("--median-time-span must be positive".into());
+        }
+
+        let scenario = match self.scenario {
+            ScenarioName::Steady => Scenario::Steady,
+            ScenarioName::Step => {
+                if !(self.step_factor.is_finite() && self.step_factor > 0.0) {
+                    return Err("--step-factor must be positive".into());
+                }
+                Scenario::Step {
+                    factor: self.step_factor,
+                }
+            }
+            ScenarioName::Oscillating => {
+                if !(0.0..1.0).contains(&self.amplitude) {
+                    return Err("--amplitude must be in [0, 1)".into());
+                }
+                Scenario::Oscillating {
+                    amplitude: self.amplitude,
+                    cycle: self.cycle,
+                }
+            }
+            ScenarioName::HashAndRun => {
+                if !(self.hopper_factor.is_finite() && self.hopper_factor >= 0.0) {
+                    return Err("--hopper-factor must not be negative".into());
+                }
+                Scenario::HashAndRun {
+                    factor: self.hopper_factor,
+                    threshold: self.hopper_threshold,
+                }
+            }
+            ScenarioName::TimeWarp => {
+                if !(0.0..=1.0).contains(&self.attacker_share) {
+                    return Err("--attacker-share must be in [0, 1]".into());
+                }
+                Scenario::TimeWarp {
+                    share: self.attacker_share,
+                }
+            }
+        };
+
+        let params = Params {
+            algorithm: match self.algorithm {
+                Algorithm::Periodic => AlgorithmKind::Periodic,
+                Algorithm::Lwma => AlgorithmKind::Lwma,
+                Algorithm::Asert => AlgorithmKind::Asert,
+            },
+            target_block_time: self.target_block_time,
+            period: self.period,
+            lwma_window: self.lwma_window,
+            asert_half_life: self.asert_half_life,
+            median_time_span: self.median_time_span,
+            ..Params::default()
+        };
+        let initial_difficulty = self.initial_difficulty.unwrap_or_else(|| {
+            equilibrium_difficulty(self.hashrate, self.target_block_time).round() as u64
+        });
+        Ok(Config {
+            params,
+            scenario,
+            blocks: self.blocks,
+            hashrate: self.hashrate,
+            initial_difficulty: initial_difficulty.max(1),
+            seed: self.seed,
+        })
+    }
+}
+
+/// Command-line spelling of `value`.
+fn name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map_or_else(String::new, |value| value.get_name().to_owned())
+}
+
+fn write_csv(out: &mut impl Write, blocks: &[BlockRecord]) -> io::Result<()> {
+    writeln!(
+        out,
+        "block,difficulty,hashrate,time,solve_time,timestamp,block_time,warped"
+    )?;
+    for block in blocks {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            block.block,
+            block.difficulty,
+            block.hashrate,
+            block.time,
+            block.solve_time,
+            block.timestamp,
+            block.block_time,
+            block.warped
+        )?;
+    }
+    Ok(())
+}
+
+fn write_summary(summary: &Summary) -> io::Result<()> {
+    let seconds = |millis: f64| millis / 1000.0;
+    let mut err = io::stderr().lock();
+    writeln!(err, "blocks:             {}", summary.blocks)?;
+    writeln!(
+        err,
+        "mean block time:    {:.3}s",
+        seconds(summary.mean_block_time)
+    )?;
+    writeln!(
+        err,
+        "block time stddev:  {:.3}s (variance {:.3}s^2)",
+        seconds(summary.block_time_stddev),
+        seconds(seconds(summary.block_time_variance))
+    )?;
+    writeln!(err, "final difficulty:   {}", summary.final_difficulty)?;
+    match (summary.blocks_to_converge, summary.time_to_converge) {
+        (Some(blocks), Some(time)) => writeln!(
+            err,
+            "converged after:    {blocks} blocks ({:.1}s)",
+            seconds(time as f64)
+        ),
+        _ => writeln!(err, "converged after:    never"),
+    }
+}
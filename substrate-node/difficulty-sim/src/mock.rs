@@ -0,0 +1,262 @@
+//! Minimal runtime around the mining pallet, so the simulator retargets with exactly
+//! the code the chain runs.
+//!
+//! The retarget parameters and the block time are thread-local instead of constants,
+//! so one process can simulate several configurations.
+
+use std::cell::Cell;
+
+use frame_support::{
+    construct_runtime,
+    traits::{ConstU32, ConstU64, Everything, Get, Hooks, Randomness, UnixTime},
+};
+use pallet_basic_pallet::difficulty::{AlgorithmKind, Asert, DifficultyAlgorithm, Lwma, Periodic};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+};
+
+pub type BlockNumber = u64;
+
+type Block = frame_system::mocking::MockBlock<Sim>;
+
+construct_runtime!(
+    pub enum Sim {
+        System: frame_system,
+        Balances: pallet_balances,
+        Mining: pallet_basic_pallet,
+    }
+);
+
+impl frame_system::Config for Sim {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Sim {
+    type Balance = u64;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU64<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ConstU32<0>;
+    type MaxReserves = ConstU32<0>;
+    type ReserveIdentifier = ();
+    type FreezeIdentifier = ();
+    type MaxFreezes = ConstU32<0>;
+    type RuntimeHoldReason = ();
+    type RuntimeFreezeReason = ();
+    type MaxHolds = ConstU32<0>;
+}
+
+impl pallet_basic_pallet::Config for Sim {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type Randomness = NoRandomness;
+    // Nobody is paid; only the difficulty matters here
+    type FindAuthor = ();
+    type TimeProvider = Clock;
+    type MaxBlockClaims = ConstU32<1>;
+    type RecentBlockWindow = ConstU32<64>;
+    type MaxSharesPerBlock = ConstU32<1>;
+    type InitialBlockReward = ConstU64<0>;
+    type HalvingInterval = ConstU64<0>;
+    type TailEmission = ConstU64<0>;
+    type MaxSupply = ConstU64<0>;
+    type CoinbaseMaturity = ConstU64<0>;
+    type RewardVestingPeriod = ConstU64<0>;
+    type MaxLockedRewards = ConstU32<1>;
+    type DifficultyAlgorithm = Configured;
+    type TargetBlockTime = TargetBlockTime;
+    type MedianTimeSpan = MedianTimeSpan;
+    type MaxFutureDrift = MaxFutureDrift;
+}
+
+/// Retarget parameters of a simulated chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    pub algorithm: AlgorithmKind,
+    /// Block time to aim for, in milliseconds
+    pub target_block_time: u64,
+    /// Blocks between periodic retargets
+    pub period: BlockNumber,
+    /// Solve times averaged by LWMA
+    pub lwma_window: u32,
+    /// Milliseconds off schedule that halve or double the ASERT difficulty
+    pub asert_half_life: u64,
+    /// Blocks whose median time a new block's time must exceed
+    pub median_time_span: u32,
+    /// How far a block's time may lead the honest clock, in milliseconds
+    pub max_future_drift: u64,
+}
+
+impl Default for Params {
+    /// The runtime's parameters
+    fn default() -> Self {
+        Self {
+            algorithm: AlgorithmKind::Periodic,
+            target_block_time: 12 * 1000,
+            period: 100,
+            lwma_window: 60,
+            asert_half_life: 60 * 60 * 1000,
+            median_time_span: 11,
+            max_future_drift: 2 * 12 * 1000,
+        }
+    }
+}
+
+thread_local! {
+    static PARAMS: Cell<Params> = Cell::new(Params::default());
+    static NOW: Cell<u64> = const { Cell::new(0) };
+}
+
+pub fn params() -> Params {
+    PARAMS.with(Cell::get)
+}
+
+pub struct TargetBlockTime;
+
+impl Get<u64> for TargetBlockTime {
+    fn get() -> u64 {
+        params().target_block_time
+    }
+}
+
+pub struct Period;
+
+impl Get<BlockNumber> for Period {
+    fn get() -> BlockNumber {
+        params().period
+    }
+}
+
+pub struct LwmaWindow;
+
+impl Get<u32> for LwmaWindow {
+    fn get() -> u32 {
+        params().lwma_window
+    }
+}
+
+pub struct AsertHalfLife;
+
+impl Get<u64> for AsertHalfLife {
+    fn get() -> u64 {
+        params().asert_half_life
+    }
+}
+
+pub struct MedianTimeSpan;
+
+impl Get<u32> for MedianTimeSpan {
+    fn get() -> u32 {
+        params().median_time_span
+    }
+}
+
+pub struct MaxFutureDrift;
+
+impl Get<u64> for MaxFutureDrift {
+    fn get() -> u64 {
+        params().max_future_drift
+    }
+}
+
+/// Time of the block being built, set by the simulator in place of the timestamp
+/// inherent.
+pub struct Clock;
+
+impl UnixTime for Clock {
+    fn now() -> core::time::Duration {
+        core::time::Duration::from_millis(NOW.with(Cell::get))
+    }
+}
+
+pub struct NoRandomness;
+
+impl Randomness<H256, BlockNumber> for NoRandomness {
+    fn random(_subject: &[u8]) -> (H256, BlockNumber) {
+        (H256::zero(), 0)
+    }
+}
+
+/// The pallet's algorithm chosen by `Params::algorithm`.
+pub struct Configured;
+
+macro_rules! configured {
+    ($method:ident($($arg:expr)?)) => {
+        match params().algorithm {
+            AlgorithmKind::Periodic => <Periodic<Period> as DifficultyAlgorithm<Sim>>::$method($($arg)?),
+            AlgorithmKind::Lwma => <Lwma<LwmaWindow> as DifficultyAlgorithm<Sim>>::$method($($arg)?),
+            AlgorithmKind::Asert => {
+                <Asert<AsertHalfLife> as DifficultyAlgorithm<Sim>>::$method($($arg)?)
+            }
+        }
+    };
+}
+
+impl DifficultyAlgorithm<Sim> for Configured {
+    fn kind() -> AlgorithmKind {
+        params().algorithm
+    }
+
+    fn history() -> u32 {
+        configured!(history())
+    }
+
+    fn next_difficulty(n: BlockNumber) -> Option<u64> {
+        configured!(next_difficulty(n))
+    }
+
+    fn next_adjustment() -> BlockNumber {
+        configured!(next_adjustment())
+    }
+}
+
+/// A fresh chain with `params`, starting at `initial_difficulty`.
+pub fn new_chain(params: Params, initial_difficulty: u64) -> sp_io::TestExternalities {
+    PARAMS.with(|cell| cell.set(params));
+    NOW.with(|cell| cell.set(0));
+    RuntimeGenesisConfig {
+        mining: pallet_basic_pallet::GenesisConfig {
+            initial_difficulty,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+    .build_storage()
+    .expect("the genesis config is valid")
+    .into()
+}
+
+/// Imports block `number` stamped with `timestamp`; must run inside the chain's
+/// externalities.
+pub fn import_block(number: BlockNumber, timestamp: u64) {
+    System::set_block_number(number);
+    Mining::on_initialize(number);
+    NOW.with(|cell| cell.set(timestamp));
+    Mining::on_finalize(number);
+}
@@ -0,0 +1,71 @@
+//! Scripted hashrate and miner behaviour.
+
+/// How the network's hashrate and block stamps evolve over a run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scenario {
+    /// Constant hashrate, every miner honest
+    Steady,
+    /// Hashrate jumps to `factor` times the base a third of the way in and drops
+    /// back two thirds of the way in
+    Step { factor: f64 },
+    /// Hashrate swings between `1 - amplitude` and `1 + amplitude` times the base,
+    /// once every `cycle` blocks
+    Oscillating { amplitude: f64, cycle: u64 },
+    /// Hash-and-run miners with `factor` times the base hashrate join while the
+    /// difficulty is below `threshold` times the base's equilibrium difficulty, and
+    /// leave as soon as it is not
+    HashAndRun { factor: f64, threshold: f64 },
+    /// A miner with `share` of the hashrate stamps its blocks with the earliest
+    /// time the chain accepts, holding the chain's clock back
+    TimeWarp { share: f64 },
+}
+
+impl Scenario {
+    /// Hashrate (hashes per second) while block `number` of `blocks` is mined at
+    /// `difficulty`.
+    pub fn hashrate(
+        &self,
+        number: u64,
+        blocks: u64,
+        base: f64,
+        difficulty: u64,
+        target_block_time: u64,
+    ) -> f64 {
+        match *self {
+            Scenario::Steady | Scenario::TimeWarp { .. } => base,
+            Scenario::Step { factor } => {
+                if number > blocks / 3 && number <= 2 * blocks / 3 {
+                    base * factor
+                } else {
+                    base
+                }
+            }
+            Scenario::Oscillating { amplitude, cycle } => {
+                let phase = number as f64 / cycle.max(1) as f64 * std::f64::consts::TAU;
+                base * (1.0 + amplitude * phase.sin())
+            }
+            Scenario::HashAndRun { factor, threshold } => {
+                let profitable = equilibrium_difficulty(base, target_block_time) * threshold;
+                if (difficulty as f64) < profitable {
+                    base * (1.0 + factor)
+                } else {
+                    base
+                }
+            }
+        }
+    }
+
+    /// Share of blocks whose miner warps its timestamps.
+    pub fn warp_share(&self) -> f64 {
+        match *self {
+            Scenario::TimeWarp { share } => share,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Difficulty at which `hashrate` finds blocks `target_block_time` milliseconds
+/// apart on average: the kernel accepts one hash in `difficulty`.
+pub fn equilibrium_difficulty(hashrate: f64, target_block_time: u64) -> f64 {
+    hashrate * target_block_time as f64 / 1000.0
+}
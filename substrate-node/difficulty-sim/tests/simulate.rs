@@ -0,0 +1,165 @@
+//! Scenarios run through the mock chain, and the statistics over them.
+
+use difficulty_sim::{
+    mock::Params,
+    scenario::{equilibrium_difficulty, Scenario},
+    simulate, BlockRecord, Config, Run,
+};
+use pallet_basic_pallet::difficulty::AlgorithmKind;
+
+const HASHRATE: f64 = 100_000.0;
+
+fn config(algorithm: AlgorithmKind, scenario: Scenario, blocks: u64) -> Config {
+    let params = Params {
+        algorithm,
+        ..Params::default()
+    };
+    Config {
+        params,
+        scenario,
+        blocks,
+        hashrate: HASHRATE,
+        initial_difficulty: equilibrium_difficulty(HASHRATE, params.target_block_time) as u64,
+        seed: 7,
+    }
+}
+
+fn record(block: u64, difficulty: u64, hashrate: f64, time: u64, solve_time: u64) -> BlockRecord {
+    BlockRecord {
+        block,
+        difficulty,
+        hashrate,
+        time,
+        solve_time,
+        timestamp: time,
+        block_time: solve_time,
+        warped: false,
+    }
+}
+
+#[test]
+fn equal_seeds_give_equal_runs() {
+    let config = config(AlgorithmKind::Lwma, Scenario::Steady, 300);
+    assert_eq!(simulate(&config), simulate(&config));
+    let other = Config {
+        seed: 8,
+        ..config.clone()
+    };
+    assert_ne!(simulate(&config), simulate(&other));
+}
+
+#[test]
+fn steady_hashrate_holds_the_target_block_time() {
+    for algorithm in [
+        AlgorithmKind::Periodic,
+        AlgorithmKind::Lwma,
+        AlgorithmKind::Asert,
+    ] {
+        let config = config(algorithm, Scenario::Steady, 3_000);
+        let summary = simulate(&config).summary(config.params.target_block_time, 0.1);
+        let target = config.params.target_block_time as f64;
+        assert!(
+            (summary.mean_block_time - target).abs() < target * 0.1,
+            "{algorithm:?}: {summary:?}"
+        );
+    }
+}
+
+#[test]
+fn difficulty_follows_a_hashrate_step() {
+    for algorithm in [
+        AlgorithmKind::Periodic,
+        AlgorithmKind::Lwma,
+        AlgorithmKind::Asert,
+    ] {
+        let config = config(algorithm, Scenario::Step { factor: 10.0 }, 3_000);
+        let run = simulate(&config);
+        let target_block_time = config.params.target_block_time;
+
+        // By the end of the fast third, the difficulty has risen well towards the
+        // tenfold hashrate's equilibrium
+        let base = equilibrium_difficulty(HASHRATE, target_block_time);
+        let fast_end = &run.blocks[2 * 3_000 / 3 - 1];
+        assert!(
+            fast_end.difficulty as f64 > 2.5 * base,
+            "{algorithm:?}: {fast_end:?}"
+        );
+
+        // and after the drop back it returns to the base's
+        let last = run.final_difficulty as f64;
+        assert!(
+            last > base / 2.0 && last < base * 2.0,
+            "{algorithm:?}: {last}"
+        );
+    }
+}
+
+#[test]
+fn time_warp_stamps_obey_the_chain_rules() {
+    let config = config(
+        AlgorithmKind::Periodic,
+        Scenario::TimeWarp { share: 0.4 },
+        1_000,
+    );
+    let run = simulate(&config);
+    assert!(run.blocks.iter().any(|block| block.warped));
+    let span = config.params.median_time_span as usize;
+    for (index, block) in run.blocks.iter().enumerate() {
+        assert!(block.block_time >= 1);
+        let recent: Vec<u64> = run.blocks[index.saturating_sub(span)..index]
+            .iter()
+            .map(|block| block.timestamp)
+            .collect();
+        if let Some(median) = pow_primitives::time::median_time_past(&recent) {
+            assert!(block.timestamp > median);
+        }
+        // Honest stamps never lag the clock unless the rules force them ahead
+        if !block.warped {
+            assert!(block.timestamp >= block.time);
+        }
+    }
+}
+
+#[test]
+fn summary_reports_moments_of_solve_times() {
+    let run = Run {
+        blocks: vec![
+            record(1, 100, 10.0, 8_000, 8_000),
+            record(2, 100, 10.0, 20_000, 12_000),
+            record(3, 100, 10.0, 36_000, 16_000),
+        ],
+        final_difficulty: 100,
+    };
+    let summary = run.summary(10_000, 0.1);
+    assert_eq!(summary.blocks, 3);
+    assert_eq!(summary.mean_block_time, 12_000.0);
+    assert_eq!(summary.block_time_variance, 32_000_000.0 / 3.0);
+    assert_eq!(summary.final_difficulty, 100);
+    // Settled from the start
+    assert_eq!(summary.blocks_to_converge, Some(0));
+    assert_eq!(summary.time_to_converge, Some(0));
+}
+
+#[test]
+fn convergence_counts_from_the_last_hashrate_change() {
+    // Equilibrium at 20 H/s and 10 s blocks is 200
+    let run = Run {
+        blocks: vec![
+            record(1, 100, 10.0, 10_000, 10_000),
+            record(2, 100, 20.0, 15_000, 5_000),
+            record(3, 150, 20.0, 22_000, 7_000),
+            record(4, 195, 20.0, 32_000, 10_000),
+            record(5, 210, 20.0, 42_000, 10_000),
+        ],
+        final_difficulty: 200,
+    };
+    let summary = run.summary(10_000, 0.1);
+    assert_eq!(summary.blocks_to_converge, Some(2));
+    assert_eq!(summary.time_to_converge, Some(22_000 - 10_000));
+
+    let unsettled = Run {
+        final_difficulty: 150,
+        ..run
+    };
+    assert_eq!(unsettled.summary(10_000, 0.1).blocks_to_converge, None);
+}
@@ -0,0 +1,74 @@
+//! Local time for the runtime's block time checks.
+//!
+//! Authors stamp blocks through the timestamp inherent. Importers reject blocks
+//! whose time runs too far ahead of their clock or is not after the median time of
+//! recent blocks, using the node's clock at the moment the inherent data is created.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parity_scale_codec::Decode;
+use pow_primitives::time::{InherentError, INHERENT_IDENTIFIER};
+use sp_core::H256;
+use sp_inherents::{Error, InherentData, InherentIdentifier};
+
+/// Provides the node's current time, in milliseconds since the Unix epoch.
+pub struct BlockTimeProvider {
+    local_time: u64,
+}
+
+impl BlockTimeProvider {
+    pub fn from_system_time() -> Self {
+        let local_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is after the Unix epoch")
+            .as_millis() as u64;
+        Self { local_time }
+    }
+}
+
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for BlockTimeProvider {
+    async fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), Error> {
+        inherent_data.put_data(INHERENT_IDENTIFIER, &self.local_time)
+    }
+
+    async fn try_handle_error(
+        &self,
+        identifier: &InherentIdentifier,
+        mut error: &[u8],
+    ) -> Option<Result<(), Error>> {
+        if *identifier != INHERENT_IDENTIFIER {
+            return None;
+        }
+        let message = match InherentError::decode(&mut error) {
+            Ok(InherentError::NotAfterMedianTimePast { timestamp, median }) => {
+                format!("block time {timestamp} is not after the median time {median}")
+            }
+            Ok(InherentError::TooFarInFuture {
+                timestamp,
+                local_time,
+                max_drift,
+            }) => format!(
+                "block time {timestamp} is more than {max_drift}ms ahead of local time {local_time}"
+            ),
+            Ok(InherentError::MissingLocalTime) => "no local time to check the block time".into(),
+            Err(error) => format!("undecodable block time error: {error}"),
+        };
+        Some(Err(Error::Application(message.into())))
+    }
+}
+
+/// Inherent data providers for a block on top of `_parent`, shared by block import,
+/// mining and dev sealing.
+pub async fn inherent_data_providers(
+    _parent: H256,
+    _: (),
+) -> Result<
+    (sp_timestamp::InherentDataProvider, BlockTimeProvider),
+    Box<dyn std::error::Error + Send + Sync>,
+> {
+    Ok((
+        sp_timestamp::InherentDataProvider::from_system_time(),
+        BlockTimeProvider::from_system_time(),
+    ))
+}
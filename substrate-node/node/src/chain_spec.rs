@@ -0,0 +1,60 @@
+use runtime::{AccountId, Balance, RuntimeGenesisConfig, UNIT, WASM_BINARY};
+use sc_service::ChainType;
+
+/// Specialized `ChainSpec` for the runtime.
+pub type ChainSpec = sc_service::GenericChainSpec<RuntimeGenesisConfig>;
+
+/// Balance of each `--endow` account on the built-in chains.
+const ENDOWMENT: Balance = 1_000_000 * UNIT;
+
+/// Genesis patch funding `endowed` with `ENDOWMENT` each.
+fn balances(endowed: &[AccountId]) -> serde_json::Value {
+    let balances: Vec<_> = endowed.iter().map(|account| (account, ENDOWMENT)).collect();
+    serde_json::json!({ "balances": balances })
+}
+
+/// Single-node chain for `--dev`, mined locally without any peers.
+pub fn development_config(endowed: &[AccountId]) -> Result<ChainSpec, String> {
+    Ok(ChainSpec::builder(
+        WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
+        None,
+    )
+    .with_name("Development")
+    .with_id("dev")
+    .with_chain_type(ChainType::Development)
+    .with_genesis_config_patch(serde_json::json!({ "balances": balances(endowed) }))
+    .build())
+}
+
+/// `--dev` chain for `--sealing`: every seal is found on the first few hashes and
+/// the difficulty never changes, so tests get blocks quickly and deterministically.
+pub fn dev_sealing_config(endowed: &[AccountId]) -> Result<ChainSpec, String> {
+    Ok(ChainSpec::builder(
+        WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
+        None,
+    )
+    .with_name("Development (dev sealing)")
+    .with_id("dev")
+    .with_chain_type(ChainType::Development)
+    .with_genesis_config_patch(serde_json::json!({
+        "balances": balances(endowed),
+        "basicPallet": {
+            "initialDifficulty": 1,
+            "fixedDifficulty": true,
+        },
+    }))
+    .build())
+}
+
+/// Multi-node chain for local testing.
+pub fn local_testnet_config(endowed: &[AccountId]) -> Result<ChainSpec, String> {
+    Ok(ChainSpec::builder(
+        WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?,
+        None,
+    )
+    .with_name("Local Testnet")
+    .with_id("local_testnet")
+    .with_chain_type(ChainType::Local)
+    .with_genesis_config_patch(serde_json::json!({ "balances": balances(endowed) }))
+    .build())
+}
@@ -0,0 +1,102 @@
+use runtime::AccountId;
+use sc_cli::RunCmd;
+
+#[derive(Debug, clap::Parser)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub subcommand: Option<Subcommand>,
+
+    #[clap(flatten)]
+    pub run: RunCmd,
+
+    #[clap(flatten)]
+    pub mining: MiningParams,
+
+    #[clap(flatten)]
+    pub genesis: GenesisParams,
+}
+
+/// Genesis state of the built-in chain specs.
+#[derive(Debug, Clone, clap::Args)]
+pub struct GenesisParams {
+    /// Account funded at genesis of the `dev` and `local` chains, as 32 bytes of hex
+    /// (the BLAKE3 hash of a Dilithium public key); may be repeated
+    #[arg(long, value_parser = parse_account)]
+    pub endow: Vec<AccountId>,
+}
+
+/// Block authoring by this node.
+#[derive(Debug, Clone, clap::Args)]
+pub struct MiningParams {
+    /// Author blocks by mining on this node
+    #[arg(long, requires = "miner")]
+    pub mine: bool,
+
+    /// Mining threads (defaults to the number of CPUs)
+    #[arg(long, requires = "mine")]
+    pub threads: Option<usize>,
+
+    /// Account credited for mined blocks, as 32 bytes of hex
+    #[arg(long, value_parser = parse_account)]
+    pub miner: Option<AccountId>,
+
+    /// Seal blocks on demand instead of mining continuously (dev chains only); the
+    /// `dev` chain then starts at difficulty 1 and never retargets
+    #[arg(long, value_enum, conflicts_with = "mine", requires = "miner")]
+    pub sealing: Option<Sealing>,
+}
+
+/// When a dev node seals blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Sealing {
+    /// As soon as a transaction enters the pool, or on `engine_createBlock`
+    Instant,
+    /// Only on `engine_createBlock`
+    Manual,
+}
+
+impl MiningParams {
+    pub fn threads(&self) -> usize {
+        match self.threads {
+            Some(threads) => threads.max(1),
+            None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+fn parse_account(hex: &str) -> Result<AccountId, String> {
+    let bytes = sp_core::bytes::from_hex(hex).map_err(|err| format!("invalid hex: {err}"))?;
+    AccountId::try_from(&bytes[..]).map_err(|()| format!("expected 32 bytes, got {}", bytes.len()))
+}
+
+#[derive(Debug, clap::Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum Subcommand {
+    /// Key management cli utilities
+    #[command(subcommand)]
+    Key(sc_cli::KeySubcommand),
+
+    /// Build a chain specification.
+    BuildSpec(sc_cli::BuildSpecCmd),
+
+    /// Validate blocks.
+    CheckBlock(sc_cli::CheckBlockCmd),
+
+    /// Export blocks.
+    ExportBlocks(sc_cli::ExportBlocksCmd),
+
+    /// Export the state of a given block into a chain spec.
+    ExportState(sc_cli::ExportStateCmd),
+
+    /// Import blocks.
+    ImportBlocks(sc_cli::ImportBlocksCmd),
+
+    /// Remove the whole chain.
+    PurgeChain(sc_cli::PurgeChainCmd),
+
+    /// Revert the chain to a previous state.
+    Revert(sc_cli::RevertCmd),
+
+    /// Db meta columns information.
+    ChainInfo(sc_cli::ChainInfoCmd),
+}
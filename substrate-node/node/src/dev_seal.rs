@@ -0,0 +1,121 @@
+//! `--sealing`: dev-chain block authoring with `sc-consensus-manual-seal`.
+//!
+//! Blocks carry the same pre-digest and seal as mined ones and go through
+//! `PowBlockImport`, so seals are real and verified; the dev sealing chain spec just
+//! keeps the difficulty at 1, where the first nonce tried almost always works.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use parity_scale_codec::Encode;
+use pow_kernel::{MinerHandle, SearchConfig};
+use pow_primitives::{Algorithm, Seal, ENGINE_ID};
+use runtime::{AccountId, Block, Header};
+use sc_consensus::BlockImportParams;
+use sc_consensus_manual_seal::{consensus::ConsensusDataProvider, EngineCommand, Error};
+use sc_consensus_pow::{PowAlgorithm, PowIntermediate, INTERMEDIATE_KEY};
+use sc_transaction_pool_api::TransactionPool;
+use sp_core::{H256, U256};
+use sp_inherents::InherentData;
+use sp_runtime::{Digest, DigestItem};
+
+use crate::{cli::Sealing, mining, pow};
+
+/// Longest a dev seal may take before sealing fails, which only happens when the
+/// chain's difficulty is far from trivial.
+const SEAL_DEADLINE: Duration = Duration::from_secs(5);
+
+/// Sealing commands: those sent through `engine_createBlock`, plus one per imported
+/// transaction with instant sealing.
+pub fn commands<P: TransactionPool>(
+    sealing: Sealing,
+    pool: &P,
+    rpc_commands: impl Stream<Item = EngineCommand<H256>> + Send + 'static,
+) -> Pin<Box<dyn Stream<Item = EngineCommand<H256>> + Send>> {
+    match sealing {
+        Sealing::Manual => Box::pin(rpc_commands),
+        Sealing::Instant => {
+            let on_transaction =
+                pool.import_notification_stream()
+                    .map(|_| EngineCommand::SealNewBlock {
+                        create_empty: false,
+                        finalize: false,
+                        parent_hash: None,
+                        sender: None,
+                    });
+            Box::pin(futures::stream::select(on_transaction, rpc_commands))
+        }
+    }
+}
+
+/// Adds the miner's pre-digest to each block and seals it with proof of work.
+pub struct PowSealProvider<A> {
+    algorithm: A,
+    miner: AccountId,
+}
+
+impl<A> PowSealProvider<A> {
+    pub fn new(algorithm: A, miner: AccountId) -> Self {
+        Self { algorithm, miner }
+    }
+}
+
+impl<A> ConsensusDataProvider<Block> for PowSealProvider<A>
+where
+    A: PowAlgorithm<Block, Difficulty = U256> + Send + Sync,
+{
+    type Proof = ();
+
+    fn create_digest(&self, _parent: &Header, _inherents: &InherentData) -> Result<Digest, Error> {
+        Ok(Digest {
+            logs: vec![DigestItem::PreRuntime(
+                ENGINE_ID,
+                mining::pre_digest(self.miner.clone()),
+            )],
+        })
+    }
+
+    fn append_block_import(
+        &self,
+        parent: &Header,
+        params: &mut BlockImportParams<Block>,
+        _inherents: &InherentData,
+        _proof: Self::Proof,
+    ) -> Result<(), Error> {
+        // `sc-consensus-pow` calls the target the difficulty
+        let target = self
+            .algorithm
+            .difficulty(parent.hash())
+            .map_err(|err| Error::StringError(err.to_string()))?;
+
+        let pre_hash = params.header.hash();
+        let search = SearchConfig {
+            deadline: Some(SEAL_DEADLINE),
+            ..SearchConfig::default()
+        };
+        let outcome = pow::search(&MinerHandle::new(), &pre_hash, target, &search);
+        let nonce = outcome.solution.ok_or_else(|| {
+            Error::StringError(format!(
+                "no seal within {SEAL_DEADLINE:?} at target {target:#x}"
+            ))
+        })?;
+
+        let seal = Seal {
+            algorithm: Algorithm::CURRENT,
+            nonce,
+            miner: self.miner.clone().into(),
+        };
+        params
+            .post_digests
+            .push(DigestItem::Seal(ENGINE_ID, seal.encode()));
+        // Saves `PowBlockImport` from asking the runtime for the target again
+        params.insert_intermediate(
+            INTERMEDIATE_KEY,
+            PowIntermediate::<U256> {
+                difficulty: Some(target),
+            },
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+//! Block import applying the cumulative-work fork choice of
+//! `pow_primitives::fork_choice`, with each block's `ChainWork` kept in auxiliary
+//! storage.
+//!
+//! Sits inside `PowBlockImport`, so it only sees blocks whose seal was verified, and
+//! overrides the fork choice that `PowBlockImport` set.
+
+use std::sync::Arc;
+
+use parity_scale_codec::{Decode, Encode};
+use pow_primitives::fork_choice::{aux_key, is_better, ChainWork};
+use runtime::Block;
+use sc_client_api::AuxStore;
+use sc_consensus::{
+    BlockCheckParams, BlockImport, BlockImportParams, ForkChoiceStrategy, ImportResult,
+};
+use sc_consensus_pow::PowAlgorithm;
+use sp_blockchain::HeaderBackend;
+use sp_consensus::Error as ConsensusError;
+use sp_core::{H256, U256};
+use sp_runtime::traits::Header as _;
+
+pub struct ForkChoiceBlockImport<I, C, A> {
+    inner: I,
+    client: Arc<C>,
+    algorithm: A,
+}
+
+impl<I, C, A> ForkChoiceBlockImport<I, C, A> {
+    pub fn new(inner: I, client: Arc<C>, algorithm: A) -> Self {
+        Self {
+            inner,
+            client,
+            algorithm,
+        }
+    }
+}
+
+impl<I: Clone, C, A: Clone> Clone for ForkChoiceBlockImport<I, C, A> {
+    fn clone(&self) -> Self {
+        Self::new(
+            self.inner.clone(),
+            self.client.clone(),
+            self.algorithm.clone(),
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl<I, C, A> BlockImport<Block> for ForkChoiceBlockImport<I, C, A>
+where
+    I: BlockImport<Block, Error = ConsensusError> + Send + Sync,
+    C: AuxStore + HeaderBackend<Block> + Send + Sync,
+    A: PowAlgorithm<Block, Difficulty = U256> + Send + Sync,
+{
+    type Error = ConsensusError;
+
+    async fn check_block(
+        &mut self,
+        block: BlockCheckParams<Block>,
+    ) -> Result<ImportResult, Self::Error> {
+        self.inner.check_block(block).await
+    }
+
+    async fn import_block(
+        &mut self,
+        mut block: BlockImportParams<Block>,
+    ) -> Result<ImportResult, Self::Error> {
+        let parent = *block.header.parent_hash();
+        // The target the block was verified against, which `sc-consensus-pow` calls
+        // its difficulty
+        let target = self
+            .algorithm
+            .difficulty(parent)
+            .map_err(|err| ConsensusError::ClientImport(err.to_string()))?;
+        let work = read_work(&*self.client, &parent)?.extend(target);
+
+        let best = self.client.info().best_hash;
+        let best_work = read_work(&*self.client, &best)?;
+
+        let hash = block.post_hash();
+        block.auxiliary.push((aux_key(&hash), Some(work.encode())));
+        block.fork_choice = Some(ForkChoiceStrategy::Custom(is_better(
+            &hash, &work, &best, &best_work,
+        )));
+        self.inner.import_block(block).await
+    }
+}
+
+/// `ChainWork` of block `hash`; genesis has no record and no work.
+fn read_work<C: AuxStore>(client: &C, hash: &H256) -> Result<ChainWork, ConsensusError> {
+    let record = client
+        .get_aux(&aux_key(hash))
+        .map_err(|err| ConsensusError::ClientImport(err.to_string()))?;
+    match record {
+        Some(bytes) => ChainWork::decode(&mut &bytes[..]).map_err(|err| {
+            ConsensusError::ClientImport(format!("corrupt chain work for {hash:?}: {err}"))
+        }),
+        None => Ok(ChainWork::default()),
+    }
+}
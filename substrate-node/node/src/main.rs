@@ -0,0 +1,19 @@
+//! Substrate node for the Blake3 runtime, sealed with BLAKE3 proof of work.
+
+// `sc_cli::Error` and `sc_service::Error` are large, and returned everywhere
+#![allow(clippy::result_large_err)]
+
+mod block_time;
+mod chain_spec;
+mod cli;
+mod command;
+mod dev_seal;
+mod fork_choice;
+mod mining;
+mod pow;
+mod rpc;
+mod service;
+
+fn main() -> sc_cli::Result<()> {
+    command::run()
+}
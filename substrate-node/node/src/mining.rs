@@ -0,0 +1,98 @@
+//! The `--mine` worker: searches nonces for the current block template with
+//! `pow-kernel` and hands solutions back to `sc-consensus-pow`.
+
+use std::thread;
+use std::time::Duration;
+
+use parity_scale_codec::Encode;
+use pow_kernel::{Backend, MinerHandle, SearchConfig};
+use pow_primitives::{Algorithm, PreDigest, Seal};
+use runtime::{AccountId, Block};
+use sc_consensus::JustificationSyncLink;
+use sc_consensus_pow::{MiningHandle, PowAlgorithm};
+use sp_core::{H256, U256};
+
+use crate::pow;
+
+/// How long one search runs before checking for a newer block template.
+const ROUND: Duration = Duration::from_millis(500);
+/// Wait between polls while there is no template (e.g. during major sync).
+const IDLE: Duration = Duration::from_millis(250);
+
+/// Pre-runtime digest naming `miner`, fixed for every block this node mines.
+pub fn pre_digest(miner: AccountId) -> Vec<u8> {
+    PreDigest {
+        algorithm: Algorithm::CURRENT,
+        miner: miner.into(),
+    }
+    .encode()
+}
+
+/// Starts mining on `threads` threads for as long as the node runs, sealing blocks
+/// for `miner`.
+pub fn spawn<A, L, Proof>(
+    worker: MiningHandle<Block, A, L, Proof>,
+    threads: usize,
+    miner: AccountId,
+) where
+    A: PowAlgorithm<Block, Difficulty = U256> + Send + Sync + 'static,
+    L: JustificationSyncLink<Block> + 'static,
+    Proof: Send + 'static,
+{
+    thread::Builder::new()
+        .name("pow-miner".into())
+        .spawn(move || mine(worker, threads, miner))
+        .expect("spawning the mining thread");
+}
+
+fn mine<A, L, Proof>(worker: MiningHandle<Block, A, L, Proof>, threads: usize, account: AccountId)
+where
+    A: PowAlgorithm<Block, Difficulty = U256> + Send + Sync + 'static,
+    L: JustificationSyncLink<Block> + 'static,
+    Proof: Send + 'static,
+{
+    let miner = MinerHandle::new();
+    let mut template: Option<H256> = None;
+    let mut next_nonce = 0u64;
+    loop {
+        let Some(metadata) = worker.metadata() else {
+            thread::sleep(IDLE);
+            continue;
+        };
+        let version = worker.version();
+        if template != Some(metadata.pre_hash) {
+            template = Some(metadata.pre_hash);
+            next_nonce = 0;
+        }
+
+        let config = SearchConfig {
+            backend: Backend::Simd,
+            threads,
+            nonces: next_nonce..u64::MAX,
+            deadline: Some(ROUND),
+        };
+        // `sc-consensus-pow` calls the target the difficulty
+        let outcome = pow::search(&miner, &metadata.pre_hash, metadata.difficulty, &config);
+        match outcome.solution {
+            // The template may have been replaced while searching
+            Some(nonce) if worker.version() == version => {
+                log::debug!(target: "pow", "Found nonce {nonce} for {:?}", metadata.pre_hash);
+                let seal = Seal {
+                    algorithm: Algorithm::CURRENT,
+                    nonce,
+                    miner: account.clone().into(),
+                };
+                futures::executor::block_on(worker.submit(seal.encode()));
+                template = None;
+            }
+            Some(_) => {}
+            // Resume after the highest nonce tried; hash counts include nonces
+            // hashed past a prefix hit
+            None => {
+                if let Some(last) = outcome.last_nonce {
+                    next_nonce = last.saturating_add(1);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! BLAKE3 proof of work for `sc-consensus-pow`, backed by `pow-kernel`.
+//!
+//! Blocks carry a `pow_primitives::PreDigest` naming the miner and a `Seal` with the
+//! nonce (see `pow-primitives`). A block is valid when both decode strictly, agree on
+//! miner and algorithm, the algorithm is `Blake3V2`, and the kernel's hash of
+//! `pre_hash || nonce`, read as a big-endian 256-bit number, meets the target that
+//! the runtime reports through `MiningApi` at the parent block.
+//!
+//! `sc-consensus-pow` calls that target the block's difficulty; it only hands it back
+//! to `verify` and to the fork choice, which `fork_choice` turns into work.
+
+use std::sync::Arc;
+
+use pallet_basic_pallet_runtime_api::MiningApi;
+use pow_kernel::{MinerHandle, SearchConfig, SearchOutcome};
+use pow_primitives::{target::Target, Algorithm, PreDigest, Seal};
+use runtime::{AccountId, Balance, Block, BlockNumber};
+use sc_consensus_pow::{Error, PowAlgorithm};
+use sp_api::ProvideRuntimeApi;
+use sp_consensus_pow::Seal as RawSeal;
+use sp_core::{H256, U256};
+use sp_runtime::generic::BlockId;
+
+/// Kernel target for `target`: its top 32 bits. A hash can only meet `target` if its
+/// 32-bit prefix meets these.
+pub fn prefix_target(target: U256) -> u32 {
+    (target >> 224).low_u32()
+}
+
+/// Whether the hash of `pre_hash || nonce` meets `target`.
+pub fn meets_target(pre_hash: &H256, nonce: u64, target: U256) -> bool {
+    Target::new(target)
+        .is_some_and(|target| target.is_met_by(&pow_kernel::pow_hash(pre_hash.as_bytes(), nonce)))
+}
+
+/// Whether `seal` solves `pre_hash` at `target` for the miner named in
+/// `pre_digest`, with `Algorithm::CURRENT`. Malformed or mismatched digests, and
+/// retired algorithms, are invalid, never ignored.
+pub fn check_seal(pre_hash: &H256, pre_digest: Option<&[u8]>, seal: &[u8], target: U256) -> bool {
+    let Some(pre_digest) = pre_digest.and_then(PreDigest::decode_strict) else {
+        return false;
+    };
+    let Some(seal) = Seal::decode_strict(seal) else {
+        return false;
+    };
+    pre_digest.algorithm == Algorithm::CURRENT
+        && seal.matches(&pre_digest)
+        && meets_target(pre_hash, seal.nonce, target)
+}
+
+/// Searches `config.nonces` for the lowest nonce solving `pre_hash` at `target`. The
+/// kernel only compares 32-bit prefixes, so each nonce it finds is checked against
+/// the whole target, and the search resumes after one that falls short.
+pub fn search(
+    miner: &MinerHandle,
+    pre_hash: &H256,
+    target: U256,
+    config: &SearchConfig,
+) -> SearchOutcome {
+    let mut config = config.clone();
+    let mut total = SearchOutcome {
+        solution: None,
+        hashes: 0,
+        last_nonce: None,
+        elapsed: Default::default(),
+    };
+    loop {
+        let outcome = miner.search(pre_hash.as_bytes(), prefix_target(target), &config);
+        total.hashes += outcome.hashes;
+        total.elapsed += outcome.elapsed;
+        match outcome.solution {
+            Some(nonce) if !meets_target(pre_hash, nonce, target) => {
+                // Other threads may have hashed past it, but not every nonce between
+                total.last_nonce = Some(nonce);
+                config.nonces.start = nonce.saturating_add(1);
+                config.deadline = config
+                    .deadline
+                    .map(|deadline| deadline.saturating_sub(outcome.elapsed));
+                if config.nonces.is_empty() || config.deadline.is_some_and(|d| d.is_zero()) {
+                    return total;
+                }
+            }
+            solution => {
+                total.solution = solution;
+                total.last_nonce = outcome.last_nonce.or(total.last_nonce);
+                return total;
+            }
+        }
+    }
+}
+
+pub struct Blake3Algorithm<C> {
+    client: Arc<C>,
+}
+
+impl<C> Blake3Algorithm<C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client }
+    }
+}
+
+// Manual impl: `C` itself need not be `Clone`
+impl<C> Clone for Blake3Algorithm<C> {
+    fn clone(&self) -> Self {
+        Self::new(self.client.clone())
+    }
+}
+
+impl<C> PowAlgorithm<Block> for Blake3Algorithm<C>
+where
+    C: ProvideRuntimeApi<Block>,
+    C::Api: MiningApi<Block, AccountId, Balance, BlockNumber, H256>,
+{
+    type Difficulty = U256;
+
+    /// The runtime's target at `parent`, as the block's "difficulty".
+    fn difficulty(&self, parent: H256) -> Result<U256, Error<Block>> {
+        self.client
+            .runtime_api()
+            .target(parent)
+            .map_err(|err| Error::Environment(format!("fetching target: {err}")))
+    }
+
+    fn verify(
+        &self,
+        _parent: &BlockId<Block>,
+        pre_hash: &H256,
+        pre_digest: Option<&[u8]>,
+        seal: &RawSeal,
+        target: U256,
+    ) -> Result<bool, Error<Block>> {
+        Ok(check_seal(pre_hash, pre_digest, seal, target))
+    }
+}
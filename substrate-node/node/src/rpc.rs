@@ -0,0 +1,62 @@
+//! JSON-RPC extensions on top of the standard Substrate RPC set.
+
+use std::sync::Arc;
+
+use futures::channel::mpsc;
+use jsonrpsee::RpcModule;
+use runtime::{AccountId, Block, Nonce};
+use sc_consensus_manual_seal::{
+    rpc::{ManualSeal, ManualSealApiServer},
+    EngineCommand,
+};
+use sc_transaction_pool_api::TransactionPool;
+use sp_api::ProvideRuntimeApi;
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::H256;
+
+pub use sc_rpc_api::DenyUnsafe;
+
+/// Full client dependencies.
+pub struct FullDeps<C, P> {
+    /// The client instance to use.
+    pub client: Arc<C>,
+    /// Transaction pool instance.
+    pub pool: Arc<P>,
+    /// Whether to deny unsafe calls
+    pub deny_unsafe: DenyUnsafe,
+    /// Sealing commands for `engine_*` calls, with `--sealing`
+    pub command_sink: Option<mpsc::Sender<EngineCommand<H256>>>,
+}
+
+/// Instantiate all full RPC extensions.
+pub fn create_full<C, P>(
+    deps: FullDeps<C, P>,
+) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
+where
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
+    C: Send + Sync + 'static,
+    C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
+    C::Api: BlockBuilder<Block>,
+    P: TransactionPool + 'static,
+{
+    use substrate_frame_rpc_system::{System, SystemApiServer};
+
+    let mut module = RpcModule::new(());
+    let FullDeps {
+        client,
+        pool,
+        deny_unsafe,
+        command_sink,
+    } = deps;
+
+    module.merge(System::new(client, pool, deny_unsafe).into_rpc())?;
+
+    if let Some(command_sink) = command_sink {
+        // `engine_createBlock` and `engine_finalizeBlock`
+        module.merge(ManualSeal::new(command_sink).into_rpc())?;
+    }
+
+    Ok(module)
+}
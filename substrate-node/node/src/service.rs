@@ -0,0 +1,264 @@
+//! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
+
+use std::{sync::Arc, time::Duration};
+
+use runtime::{self, Block, RuntimeApi};
+use sc_consensus::BoxBlockImport;
+use sc_consensus_manual_seal::ManualSealParams;
+use sc_consensus_pow::PowBlockImport;
+pub use sc_executor::NativeElseWasmExecutor;
+use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_telemetry::{Telemetry, TelemetryWorker};
+
+use crate::{
+    cli::MiningParams, dev_seal::PowSealProvider, fork_choice::ForkChoiceBlockImport,
+    pow::Blake3Algorithm,
+};
+
+/// How long the mining worker waits for the proposer to build a block.
+const PROPOSE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time budget handed to the proposer for filling a block.
+const BUILD_TIME: Duration = Duration::from_secs(10);
+
+// Our native executor instance.
+pub struct ExecutorDispatch;
+
+impl sc_executor::NativeExecutionDispatch for ExecutorDispatch {
+    type ExtendHostFunctions = ();
+
+    fn dispatch(method: &str, data: &[u8]) -> Option<Vec<u8>> {
+        runtime::api::dispatch(method, data)
+    }
+
+    fn native_version() -> sc_executor::NativeVersion {
+        runtime::native_version()
+    }
+}
+
+pub(crate) type FullClient =
+    sc_service::TFullClient<Block, RuntimeApi, NativeElseWasmExecutor<ExecutorDispatch>>;
+type FullBackend = sc_service::TFullBackend<Block>;
+type FullSelectChain = sc_consensus::LongestChain<FullBackend, Block>;
+
+pub type Service = sc_service::PartialComponents<
+    FullClient,
+    FullBackend,
+    FullSelectChain,
+    sc_consensus::DefaultImportQueue<Block>,
+    sc_transaction_pool::FullPool<Block, FullClient>,
+    Option<Telemetry>,
+>;
+
+/// Block import that checks the seal (and the runtime's inherents), then picks the
+/// best chain by cumulative work before handing the block to the client.
+fn pow_block_import(
+    client: Arc<FullClient>,
+    select_chain: FullSelectChain,
+) -> BoxBlockImport<Block> {
+    let fork_choice = ForkChoiceBlockImport::new(
+        client.clone(),
+        client.clone(),
+        Blake3Algorithm::new(client.clone()),
+    );
+    Box::new(PowBlockImport::new(
+        fork_choice,
+        client.clone(),
+        Blake3Algorithm::new(client),
+        0, // Check inherents from genesis on
+        select_chain,
+        crate::block_time::inherent_data_providers,
+    ))
+}
+
+pub fn new_partial(config: &Configuration) -> Result<Service, ServiceError> {
+    let telemetry = config
+        .telemetry_endpoints
+        .clone()
+        .filter(|x| !x.is_empty())
+        .map(|endpoints| -> Result<_, sc_telemetry::Error> {
+            let worker = TelemetryWorker::new(16)?;
+            let telemetry = worker.handle().new_telemetry(endpoints);
+            Ok((worker, telemetry))
+        })
+        .transpose()?;
+
+    let executor = sc_service::new_native_or_wasm_executor(config);
+    let (client, backend, keystore_container, task_manager) =
+        sc_service::new_full_parts::<Block, RuntimeApi, _>(
+            config,
+            telemetry.as_ref().map(|(_, telemetry)| telemetry.handle()),
+            executor,
+        )?;
+    let client = Arc::new(client);
+
+    let telemetry = telemetry.map(|(worker, telemetry)| {
+        task_manager
+            .spawn_handle()
+            .spawn("telemetry", None, worker.run());
+        telemetry
+    });
+
+    let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+    let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+        config.transaction_pool.clone(),
+        config.role.is_authority().into(),
+        config.prometheus_registry(),
+        task_manager.spawn_essential_handle(),
+        client.clone(),
+    );
+
+    let import_queue = sc_consensus_pow::import_queue(
+        pow_block_import(client.clone(), select_chain.clone()),
+        None,
+        Blake3Algorithm::new(client.clone()),
+        &task_manager.spawn_essential_handle(),
+        config.prometheus_registry(),
+    )?;
+
+    Ok(sc_service::PartialComponents {
+        client,
+        backend,
+        task_manager,
+        import_queue,
+        keystore_container,
+        select_chain,
+        transaction_pool,
+        other: telemetry,
+    })
+}
+
+/// Builds a new service for a full client.
+pub fn new_full(config: Configuration, mining: MiningParams) -> Result<TaskManager, ServiceError> {
+    let sc_service::PartialComponents {
+        client,
+        backend,
+        mut task_manager,
+        import_queue,
+        keystore_container,
+        select_chain,
+        transaction_pool,
+        other: mut telemetry,
+    } = new_partial(&config)?;
+
+    let net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
+
+    let (network, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &config,
+            net_config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            spawn_handle: task_manager.spawn_handle(),
+            import_queue,
+            block_announce_validator_builder: None,
+            warp_sync_params: None,
+            block_relay: None,
+        })?;
+
+    let prometheus_registry = config.prometheus_registry().cloned();
+
+    let (command_sink, rpc_commands) = match mining.sealing {
+        Some(_) => {
+            let (sink, commands) = futures::channel::mpsc::channel(1024);
+            (Some(sink), Some(commands))
+        }
+        None => (None, None),
+    };
+
+    let rpc_extensions_builder = {
+        let client = client.clone();
+        let pool = transaction_pool.clone();
+
+        Box::new(move |deny_unsafe, _| {
+            let deps = crate::rpc::FullDeps {
+                client: client.clone(),
+                pool: pool.clone(),
+                deny_unsafe,
+                command_sink: command_sink.clone(),
+            };
+            crate::rpc::create_full(deps).map_err(Into::into)
+        })
+    };
+
+    let _rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        network,
+        client: client.clone(),
+        keystore: keystore_container.keystore(),
+        task_manager: &mut task_manager,
+        transaction_pool: transaction_pool.clone(),
+        rpc_builder: rpc_extensions_builder,
+        backend,
+        system_rpc_tx,
+        tx_handler_controller,
+        sync_service: sync_service.clone(),
+        config,
+        telemetry: telemetry.as_mut(),
+    })?;
+
+    if let (Some(sealing), Some(rpc_commands), Some(miner)) =
+        (mining.sealing, rpc_commands, mining.miner.clone())
+    {
+        let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+            task_manager.spawn_handle(),
+            client.clone(),
+            transaction_pool.clone(),
+            prometheus_registry.as_ref(),
+            telemetry.as_ref().map(|x| x.handle()),
+        );
+
+        let authorship = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+            block_import: pow_block_import(client.clone(), select_chain.clone()),
+            env: proposer_factory,
+            client: client.clone(),
+            commands_stream: crate::dev_seal::commands(sealing, &*transaction_pool, rpc_commands),
+            pool: transaction_pool,
+            select_chain,
+            consensus_data_provider: Some(Box::new(PowSealProvider::new(
+                Blake3Algorithm::new(client.clone()),
+                miner,
+            ))),
+            create_inherent_data_providers: crate::block_time::inherent_data_providers,
+        });
+
+        task_manager.spawn_essential_handle().spawn_blocking(
+            "manual-seal",
+            Some("block-authoring"),
+            authorship,
+        );
+        log::info!("🔨 Sealing blocks on demand ({sealing:?})");
+    } else if let (true, Some(miner)) = (mining.mine, mining.miner.clone()) {
+        let proposer_factory = sc_basic_authorship::ProposerFactory::new(
+            task_manager.spawn_handle(),
+            client.clone(),
+            transaction_pool,
+            prometheus_registry.as_ref(),
+            telemetry.as_ref().map(|x| x.handle()),
+        );
+
+        let (worker, worker_task) = sc_consensus_pow::start_mining_worker(
+            pow_block_import(client.clone(), select_chain.clone()),
+            client.clone(),
+            select_chain,
+            Blake3Algorithm::new(client.clone()),
+            proposer_factory,
+            sync_service.clone(),
+            sync_service,
+            Some(crate::mining::pre_digest(miner.clone())),
+            crate::block_time::inherent_data_providers,
+            PROPOSE_TIMEOUT,
+            BUILD_TIME,
+        );
+
+        task_manager.spawn_essential_handle().spawn_blocking(
+            "pow-mining-worker",
+            Some("block-authoring"),
+            worker_task,
+        );
+        crate::mining::spawn(worker, mining.threads(), miner);
+        log::info!("⛏  Mining with {} thread(s)", mining.threads());
+    }
+
+    network_starter.start_network();
+    Ok(task_manager)
+}
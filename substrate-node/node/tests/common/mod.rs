@@ -0,0 +1,57 @@
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+pub const TIMEOUT: Duration = Duration::from_secs(120);
+pub const MINER: &str = "0x0101010101010101010101010101010101010101010101010101010101010101";
+
+/// Kills the node when the test ends, passing or not.
+pub struct Node {
+    child: Child,
+    /// Log lines, which Substrate writes to stderr
+    pub lines: Receiver<String>,
+}
+
+impl Node {
+    /// Starts a throwaway `--dev` node with `args`, without networking extras.
+    pub fn dev(args: &[&str]) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_coin-node"))
+            .args(["--dev", "--tmp", "--port", "0", "--rpc-port", "0"])
+            .args(["--no-prometheus", "--no-telemetry"])
+            .args(args)
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("starting coin-node");
+
+        // Forward lines so waits below can time out
+        let (lines_tx, lines) = mpsc::channel();
+        let stderr = BufReader::new(child.stderr.take().unwrap());
+        thread::spawn(move || {
+            for line in stderr.lines().map_while(Result::ok) {
+                if lines_tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { child, lines }
+    }
+
+    /// Waits for a log line containing `needle`.
+    pub fn wait_for(&self, needle: &str) -> Option<String> {
+        while let Ok(line) = self.lines.recv_timeout(TIMEOUT) {
+            if line.contains(needle) {
+                return Some(line);
+            }
+        }
+        None
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
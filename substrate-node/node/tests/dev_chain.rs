@@ -0,0 +1,15 @@
+//! Runs `coin-node --dev --mine` with no network access and waits for it to mine
+//! and import blocks on its own.
+
+mod common;
+
+use common::{Node, MINER, TIMEOUT};
+
+#[test]
+fn dev_node_mines_offline() {
+    let node = Node::dev(&["--mine", "--threads", "1", "--miner", MINER]);
+    assert!(
+        node.wait_for("Imported #2").is_some(),
+        "no block #2 mined within {TIMEOUT:?}"
+    );
+}
@@ -0,0 +1,50 @@
+//! `--sealing manual` produces blocks only on `engine_createBlock`, sealed with
+//! proof of work that `PowBlockImport` accepts.
+
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use common::{Node, MINER};
+
+/// Posts a JSON-RPC request to the node's HTTP endpoint and returns the raw response.
+fn rpc(addr: &str, method: &str, params: &str) -> String {
+    let body = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{method}","params":{params}}}"#);
+    let mut stream = TcpStream::connect(addr).expect("connecting to RPC");
+    write!(
+        stream,
+        "POST / HTTP/1.1\r\nHost: {addr}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn manual_seal_creates_blocks_on_request() {
+    let node = Node::dev(&["--sealing", "manual", "--miner", MINER]);
+    let line = node
+        .wait_for("Running JSON-RPC server: addr=")
+        .expect("RPC server did not start");
+    let addr = line
+        .split("addr=")
+        .nth(1)
+        .and_then(|rest| rest.split([',', ' ']).next())
+        .expect("RPC address in log line")
+        .to_string();
+
+    for number in 1..=2 {
+        let response = rpc(&addr, "engine_createBlock", "[true, false, null]");
+        assert!(response.contains(r#""hash""#), "{response}");
+        assert!(
+            node.wait_for(&format!("Imported #{number}")).is_some(),
+            "block #{number} not imported"
+        );
+    }
+
+    let header = rpc(&addr, "chain_getHeader", "[]");
+    assert!(header.contains(r#""number":"0x2""#), "{header}");
+}
@@ -0,0 +1,47 @@
+//! Runtime API exposing the mining state of `pallet-basic-pallet`, so miners and the
+//! node read difficulty and rewards through the runtime instead of decoding storage.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use parity_scale_codec::Codec;
+use sp_core::U256;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Mining state and a dry-run of the proof-of-work check.
+    ///
+    /// Version 2 added `matured_rewards`.
+    #[api_version(2)]
+    pub trait MiningApi<AccountId, Balance, BlockNumber, Hash>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+        Hash: Codec,
+    {
+        /// Current mining difficulty.
+        fn difficulty() -> u64;
+
+        /// Current target: a proof-of-work hash, read as a 256-bit big-endian
+        /// number, must not exceed it.
+        fn target() -> U256;
+
+        /// Block at which the difficulty is next adjusted.
+        fn next_retarget_block() -> BlockNumber;
+
+        /// Reward credited for the next mined block.
+        fn next_block_reward() -> Balance;
+
+        /// Rewards credited to `who` and not yet claimed, locked or not.
+        fn pending_rewards(who: AccountId) -> Balance;
+
+        /// Part of the pending rewards that has matured and vested, and that
+        /// `claim_rewards` would pay out now.
+        #[api_version(2)]
+        fn matured_rewards(who: AccountId) -> Balance;
+
+        /// Whether `submit_block(nonce, block_hash)` from `who` would pass the
+        /// proof-of-work check at the current difficulty. Changes no state.
+        fn verify_work(who: AccountId, nonce: Vec<u8>, block_hash: Hash) -> bool;
+    }
+}
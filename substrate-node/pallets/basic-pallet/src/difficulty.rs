@@ -0,0 +1,192 @@
+//! Difficulty algorithms the runtime chooses from through `Config::DifficultyAlgorithm`.
+//!
+//! Each block's time and difficulty are recorded at the end of the block; the
+//! algorithm then decides the difficulty of the next one. The integer math lives in
+//! `pow_primitives::difficulty`.
+
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::BlockNumberFor;
+use pow_primitives::difficulty;
+use sp_runtime::traits::{One, SaturatedConversion, Saturating, Zero};
+use sp_std::{marker::PhantomData, prelude::*};
+
+use crate::{
+    AsertAnchor, BlockDifficulties, BlockTimestamps, Config, LastDifficultyAdjustment, Pallet,
+};
+
+/// Which algorithm set the difficulty; stored so that a runtime upgrade switching
+/// algorithms is noticed (see `migrations::SwitchDifficultyAlgorithm`).
+#[derive(
+    Clone, Copy, PartialEq, Eq, Default, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug,
+)]
+pub enum AlgorithmKind {
+    /// What chains ran before algorithms were selectable
+    #[default]
+    Periodic,
+    Lwma,
+    Asert,
+}
+
+/// Block the ASERT schedule is measured from.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+pub struct Anchor<BlockNumber> {
+    pub height: BlockNumber,
+    /// Time of the anchor block, in milliseconds
+    pub timestamp: u64,
+    /// Difficulty of the block after the anchor
+    pub difficulty: u64,
+}
+
+/// Decides the difficulty of the next block.
+pub trait DifficultyAlgorithm<T: Config> {
+    fn kind() -> AlgorithmKind;
+
+    /// Blocks of recorded times and difficulties the algorithm reads, besides the
+    /// current one; older records are pruned.
+    fn history() -> u32;
+
+    /// Difficulty of the block after `n`, called at the end of block `n` once its
+    /// time and difficulty are recorded. `None` keeps the current difficulty.
+    fn next_difficulty(n: BlockNumberFor<T>) -> Option<u64>;
+
+    /// Block at whose end the difficulty is next recomputed, seen from the state
+    /// after the latest block.
+    fn next_adjustment() -> BlockNumberFor<T>;
+}
+
+/// Bitcoin-style retarget once every `Period` blocks, scaling by the window's target
+/// over actual time, at most 4x either way.
+pub struct Periodic<Period>(PhantomData<Period>);
+
+impl<T: Config, Period: Get<BlockNumberFor<T>>> DifficultyAlgorithm<T> for Periodic<Period> {
+    fn kind() -> AlgorithmKind {
+        AlgorithmKind::Periodic
+    }
+
+    fn history() -> u32 {
+        Period::get().saturated_into()
+    }
+
+    fn next_difficulty(n: BlockNumberFor<T>) -> Option<u64> {
+        let last = Pallet::<T>::last_difficulty_adjustment();
+        if n < <Self as DifficultyAlgorithm<T>>::next_adjustment() {
+            return None;
+        }
+
+        // Genesis has no recorded time, so the first window starts at block 1
+        let (start, start_time) = match <BlockTimestamps<T>>::try_get(last) {
+            Ok(time) => (last, time),
+            Err(()) => (
+                last + One::one(),
+                <BlockTimestamps<T>>::try_get(last + One::one()).ok()?,
+            ),
+        };
+        let blocks: u64 = n.saturating_sub(start).saturated_into();
+        if blocks == 0 {
+            return None;
+        }
+
+        let actual_time = <BlockTimestamps<T>>::get(n).saturating_sub(start_time);
+        let target_time = T::TargetBlockTime::get().saturating_mul(blocks);
+        Some(difficulty::retarget(
+            Pallet::<T>::current_difficulty(),
+            actual_time,
+            target_time,
+        ))
+    }
+
+    fn next_adjustment() -> BlockNumberFor<T> {
+        Pallet::<T>::last_difficulty_adjustment().saturating_add(Period::get().max(One::one()))
+    }
+}
+
+/// Linearly weighted moving average over the last `Window` solve times, every block.
+pub struct Lwma<Window>(PhantomData<Window>);
+
+impl<T: Config, Window: Get<u32>> DifficultyAlgorithm<T> for Lwma<Window> {
+    fn kind() -> AlgorithmKind {
+        AlgorithmKind::Lwma
+    }
+
+    fn history() -> u32 {
+        Window::get()
+    }
+
+    fn next_difficulty(n: BlockNumberFor<T>) -> Option<u64> {
+        let mut blocks = Vec::with_capacity(Window::get() as usize + 1);
+        let mut number = n.saturating_sub(Window::get().into());
+        while number <= n {
+            // Genesis and blocks before a switch to LWMA have no records
+            if let (Ok(time), Ok(difficulty)) = (
+                <BlockTimestamps<T>>::try_get(number),
+                <BlockDifficulties<T>>::try_get(number),
+            ) {
+                blocks.push((time, difficulty));
+            }
+            number += One::one();
+        }
+        difficulty::lwma(&blocks, T::TargetBlockTime::get())
+    }
+
+    fn next_adjustment() -> BlockNumberFor<T> {
+        frame_system::Pallet::<T>::block_number() + One::one()
+    }
+}
+
+/// Absolutely scheduled exponential adjustment (aserti3-2d) every block: the
+/// difficulty halves for every `HalfLife` milliseconds the chain falls behind its
+/// schedule since the anchor block, and doubles for every `HalfLife` ahead.
+pub struct Asert<HalfLife>(PhantomData<HalfLife>);
+
+impl<T: Config, HalfLife: Get<u64>> DifficultyAlgorithm<T> for Asert<HalfLife> {
+    fn kind() -> AlgorithmKind {
+        AlgorithmKind::Asert
+    }
+
+    fn history() -> u32 {
+        0
+    }
+
+    fn next_difficulty(n: BlockNumberFor<T>) -> Option<u64> {
+        let now = <BlockTimestamps<T>>::get(n);
+        // The first block with a recorded time anchors the schedule
+        let Some(anchor) = <AsertAnchor<T>>::get() else {
+            <AsertAnchor<T>>::put(Anchor {
+                height: n,
+                timestamp: now,
+                difficulty: Pallet::<T>::current_difficulty(),
+            });
+            return None;
+        };
+
+        let blocks: u64 = n.saturating_sub(anchor.height).saturated_into();
+        Some(difficulty::asert(
+            anchor.difficulty,
+            blocks,
+            now.saturating_sub(anchor.timestamp),
+            T::TargetBlockTime::get(),
+            HalfLife::get(),
+        ))
+    }
+
+    fn next_adjustment() -> BlockNumberFor<T> {
+        frame_system::Pallet::<T>::block_number() + One::one()
+    }
+}
+
+/// Removes recorded times and difficulties that `T::DifficultyAlgorithm` no longer
+/// reads at the end of block `n`.
+pub(crate) fn prune_history<T: Config>(n: BlockNumberFor<T>) {
+    let history: BlockNumberFor<T> = T::DifficultyAlgorithm::history().into();
+    let mut keep_from = n.saturating_sub(history);
+    // A pending periodic window may start at the last adjustment; with a fixed
+    // difficulty no retarget is pending
+    if !Pallet::<T>::fixed_difficulty() {
+        keep_from = keep_from.min(<LastDifficultyAdjustment<T>>::get());
+    }
+    if !keep_from.is_zero() {
+        let stale = keep_from - One::one();
+        <BlockTimestamps<T>>::remove(stale);
+        <BlockDifficulties<T>>::remove(stale);
+    }
+}
@@ -2,67 +2,222 @@
 
 pub use pallet::*;
 
+pub mod difficulty;
+pub mod migrations;
+pub mod rewards;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
-        dispatch::DispatchResultWithPostInfo,
+        inherent::InherentData,
         pallet_prelude::*,
-        traits::{Currency, ExistenceRequirement, Randomness},
+        traits::{
+            fungible::{Inspect, Mutate},
+            FindAuthor, Randomness, UnixTime,
+        },
         transactional,
     };
     use frame_system::pallet_prelude::*;
     // use the runtime's configured hasher (Blake3) instead of Blake2
-    use sp_runtime::traits::Hash as HasherTrait;
-    use sp_runtime::{
-        traits::{CheckedAdd, CheckedSub, Hash, SaturatedConversion, StaticLookup},
-        ArithmeticError,
+    use crate::difficulty::{AlgorithmKind, Anchor, DifficultyAlgorithm};
+    use crate::rewards::{EmissionLedger, LockedReward};
+    use coin_tokenomics::RewardSchedule;
+    use pow_primitives::{
+        target::{Target, U256},
+        time::{self, InherentError},
     };
+    use sp_runtime::traits::Hash as HasherTrait;
+    use sp_runtime::traits::{One, SaturatedConversion, Saturating, Zero};
     use sp_std::prelude::*;
 
+    /// 1: the difficulty is stored as a `Target` (see `migrations::DifficultyToTarget`)
+    /// 2: rewards are locked per block (see `migrations::LockRewardsPerBlock`)
+    /// 3: issuance is tracked in `Emission` (see `migrations::StartEmissionLedger`)
+    pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(_);
+
     // Define the pallet's configuration trait
     #[pallet::config]
     pub trait Config: frame_system::Config {
         /// The overarching event type
-        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
-        /// The currency mechanism
-        type Currency: Currency<Self::AccountId>;
+        /// The currency rewards are minted in
+        type Currency: Mutate<Self::AccountId>;
 
         /// The source of randomness
-        type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+        type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
+
+        /// Finds the miner of the current block in its pre-runtime digests
+        type FindAuthor: FindAuthor<Self::AccountId>;
+
+        /// The time of the current block, set by the timestamp inherent
+        type TimeProvider: UnixTime;
 
         /// The maximum number of blocks that can be claimed in a single transaction
         #[pallet::constant]
         type MaxBlockClaims: Get<u32>;
 
-        /// The difficulty adjustment period in blocks
+        /// How many of the most recent blocks a share's `block_hash` may name; at
+        /// most `frame_system::Config::BlockHashCount`
+        #[pallet::constant]
+        type RecentBlockWindow: Get<u32>;
+
+        /// How many shares may be mined against any one block
+        #[pallet::constant]
+        type MaxSharesPerBlock: Get<u32>;
+
+        /// Reward of the first blocks, before any halving
+        #[pallet::constant]
+        type InitialBlockReward: Get<BalanceOf<Self>>;
+
+        /// Blocks between halvings of the block reward; zero never halves
+        #[pallet::constant]
+        type HalvingInterval: Get<BlockNumberFor<Self>>;
+
+        /// Least reward of a block, however many halvings have passed
+        #[pallet::constant]
+        type TailEmission: Get<BalanceOf<Self>>;
+
+        /// Most that block rewards may ever add up to
+        #[pallet::constant]
+        type MaxSupply: Get<BalanceOf<Self>>;
+
+        /// Blocks a mined block's reward stays locked for, counted from that block
+        /// (see `rewards`)
+        #[pallet::constant]
+        type CoinbaseMaturity: Get<BlockNumberFor<Self>>;
+
+        /// Blocks over which a matured reward is released linearly; zero releases
+        /// it all at maturity
         #[pallet::constant]
-        type DifficultyAdjustmentPeriod: Get<Self::BlockNumber>;
+        type RewardVestingPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Most block rewards an account may have locked at once; beyond that, new
+        /// rewards are added to the newest one
+        #[pallet::constant]
+        type MaxLockedRewards: Get<u32>;
+
+        /// How the difficulty of the next block is decided (see `difficulty`);
+        /// switching it needs `migrations::SwitchDifficultyAlgorithm`
+        type DifficultyAlgorithm: DifficultyAlgorithm<Self>;
 
         /// The target block time in milliseconds
         #[pallet::constant]
         type TargetBlockTime: Get<u64>;
+
+        /// Number of recent blocks whose median time a new block's time must exceed
+        #[pallet::constant]
+        type MedianTimeSpan: Get<u32>;
+
+        /// How far (in milliseconds) a block's time may run ahead of the importing
+        /// node's clock
+        #[pallet::constant]
+        type MaxFutureDrift: Get<u64>;
     }
 
     // Define the pallet's storage items
+    /// Target the proof of work must meet; the difficulty is derived from it
     #[pallet::storage]
-    #[pallet::getter(fn current_difficulty)]
-    pub type CurrentDifficulty<T> = StorageValue<_, u64, ValueQuery>;
+    #[pallet::getter(fn current_target)]
+    pub type CurrentTarget<T> = StorageValue<_, Target, ValueQuery>;
+
+    /// When set, the difficulty is never retargeted (dev chains with instant sealing)
+    #[pallet::storage]
+    #[pallet::getter(fn fixed_difficulty)]
+    pub type FixedDifficulty<T> = StorageValue<_, bool, ValueQuery>;
 
     #[pallet::storage]
     #[pallet::getter(fn block_timestamps)]
     pub type BlockTimestamps<T: Config> =
-        StorageMap<_, Twox64Concat, T::BlockNumber, u64, ValueQuery>;
+        StorageMap<_, Twox64Concat, BlockNumberFor<T>, u64, ValueQuery>;
+
+    /// Difficulty each recent block was mined at, kept as long as the difficulty
+    /// algorithm reads it
+    #[pallet::storage]
+    #[pallet::getter(fn block_difficulties)]
+    pub type BlockDifficulties<T: Config> =
+        StorageMap<_, Twox64Concat, BlockNumberFor<T>, u64, OptionQuery>;
+
+    /// Times of the last `MedianTimeSpan` blocks, oldest first
+    #[pallet::storage]
+    #[pallet::getter(fn recent_timestamps)]
+    pub type RecentTimestamps<T: Config> =
+        StorageValue<_, BoundedVec<u64, T::MedianTimeSpan>, ValueQuery>;
 
     #[pallet::storage]
     #[pallet::getter(fn last_difficulty_adjustment)]
-    pub type LastDifficultyAdjustment<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+    pub type LastDifficultyAdjustment<T: Config> = StorageValue<_, BlockNumberFor<T>, ValueQuery>;
+
+    /// Algorithm that set the current difficulty
+    #[pallet::storage]
+    #[pallet::getter(fn active_difficulty_algorithm)]
+    pub type ActiveDifficultyAlgorithm<T> = StorageValue<_, AlgorithmKind, ValueQuery>;
 
+    /// Block the ASERT schedule is measured from, set by its first adjustment
+    #[pallet::storage]
+    #[pallet::getter(fn asert_anchor)]
+    pub type AsertAnchor<T: Config> = StorageValue<_, Anchor<BlockNumberFor<T>>, OptionQuery>;
+
+    /// Miner of the current block and the difficulty it was mined at, paid out once
+    /// the block's hash is known (in the next block's `on_initialize`)
+    #[pallet::storage]
+    pub type BlockAuthor<T: Config> = StorageValue<_, (T::AccountId, u64), OptionQuery>;
+
+    /// Proof-of-work hashes of accepted shares, by the number of the block they
+    /// were mined against; dropped once that block leaves the `RecentBlockWindow`
+    #[pallet::storage]
+    pub type SolvedWork<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        BlockNumberFor<T>,
+        Blake2_128Concat,
+        T::Hash,
+        (),
+        OptionQuery,
+    >;
+
+    /// Number of `SolvedWork` entries under each block number
+    #[pallet::storage]
+    pub type SolvedWorkCount<T: Config> =
+        StorageMap<_, Twox64Concat, BlockNumberFor<T>, u32, ValueQuery>;
+
+    /// Block rewards credited so far, claimed or not; at most `MaxSupply`
+    #[pallet::storage]
+    #[pallet::getter(fn total_minted)]
+    pub type TotalMinted<T> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// How the pallet's own rules changed the currency's total issuance (see
+    /// `rewards::EmissionLedger`)
+    #[pallet::storage]
+    #[pallet::getter(fn emission)]
+    pub type Emission<T> = StorageValue<_, EmissionLedger<BalanceOf<T>>, ValueQuery>;
+
+    /// Rewards credited to each account and not claimed yet, locked or not
     #[pallet::storage]
     #[pallet::getter(fn mining_rewards)]
     pub type MiningRewards<T: Config> =
         StorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
 
+    /// The block rewards behind `MiningRewards`, oldest first
+    #[pallet::storage]
+    #[pallet::getter(fn locked_rewards)]
+    pub type LockedRewards<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        BoundedVec<LockedReward<BlockNumberFor<T>, BalanceOf<T>>, T::MaxLockedRewards>,
+        ValueQuery,
+    >;
+
     // Define the pallet's events
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
@@ -70,11 +225,17 @@ pub mod pallet {
         /// A new block was successfully mined
         BlockMined(T::AccountId, T::Hash, u64),
 
+        /// A side-pool share met the difficulty target
+        ShareSubmitted(T::AccountId, T::Hash, u64),
+
         /// The mining difficulty was adjusted
         DifficultyAdjusted(u64, u64),
 
         /// Mining rewards were claimed
         RewardsClaimed(T::AccountId, BalanceOf<T>),
+
+        /// A runtime upgrade switched the difficulty algorithm (from, to)
+        DifficultyAlgorithmSwitched(AlgorithmKind, AlgorithmKind),
     }
 
     // Define the pallet's errors
@@ -94,86 +255,171 @@ pub mod pallet {
 
         /// Arithmetic overflow
         ArithmeticOverflow,
+
+        /// The block hash is not one of the last `RecentBlockWindow` blocks
+        StaleBlockHash,
+
+        /// This proof of work was already submitted
+        DuplicateWork,
+
+        /// The block already has `MaxSharesPerBlock` shares mined against it
+        TooManyShares,
     }
 
     // Define the pallet's hooks
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-        fn on_initialize(n: T::BlockNumber) -> Weight {
-            // Record the timestamp of this block
-            let now = T::TimeProvider::now().as_millis() as u64;
-            <BlockTimestamps<T>>::insert(n, now);
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            // Taking the parent's author, and reading this block's digest
+            let mut weight = T::DbWeight::get().reads_writes(2, 1);
+
+            // Pay the coinbase of the parent block, whose hash is now known
+            if let Some((miner, difficulty)) = <BlockAuthor<T>>::take() {
+                let parent_hash = <frame_system::Pallet<T>>::parent_hash();
+                let parent = n.saturating_sub(One::one());
+                Self::reward_block_author(miner, parent, parent_hash, difficulty);
+                // The parent hash, then `TotalMinted`, `MiningRewards`,
+                // `LockedRewards` and the event
+                weight.saturating_accrue(T::DbWeight::get().reads_writes(4, 4));
+            }
+
+            // Remember who mined this block, at the difficulty it was mined at
+            let digest = <frame_system::Pallet<T>>::digest();
+            let pre_runtime_digests = digest
+                .logs()
+                .iter()
+                .filter_map(|item| item.as_pre_runtime());
+            if let Some(miner) = T::FindAuthor::find_author(pre_runtime_digests) {
+                <BlockAuthor<T>>::put((miner, Self::current_difficulty()));
+                weight.saturating_accrue(T::DbWeight::get().reads_writes(1, 1));
+            }
 
-            // Check if it's time to adjust difficulty
-            let adjustment_period = T::DifficultyAdjustmentPeriod::get();
-            let last_adjustment = Self::last_difficulty_adjustment();
-            
-            if n > last_adjustment && (n - last_adjustment) >= adjustment_period {
+            weight
+        }
+
+        fn on_finalize(n: BlockNumberFor<T>) {
+            // The timestamp inherent has set this block's time by now
+            let now = T::TimeProvider::now().as_millis().saturated_into::<u64>();
+            assert!(
+                time::check_median_time_past(now, &Self::recent_timestamps()).is_ok(),
+                "The block time must be later than the median time of recent blocks"
+            );
+            Self::record_block_time(n, now);
+            <BlockDifficulties<T>>::insert(n, Self::current_difficulty());
+
+            if !Self::fixed_difficulty() {
                 Self::adjust_difficulty(n);
             }
+            crate::difficulty::prune_history::<T>(n);
+            Self::prune_solved_work(n);
+        }
 
-            Weight::zero()
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::check_emission_ledger().map_err(Into::into)
+        }
+
+        fn integrity_test() {
+            let window: u64 = T::RecentBlockWindow::get().into();
+            assert!(window > 0, "RecentBlockWindow must not be zero");
+            assert!(
+                window <= T::BlockHashCount::get().saturated_into::<u64>(),
+                "RecentBlockWindow must not exceed the block hashes frame_system keeps"
+            );
+            assert!(
+                T::MaxLockedRewards::get() > 0,
+                "MaxLockedRewards must not be zero"
+            );
         }
     }
 
     // Define the pallet's dispatchable functions
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Submit a mined block
-        #[pallet::weight(10_000)]
+        /// Submit a share for a share-based side pool
+        ///
+        /// Shares are proofs of work against `block_hash` at the current difficulty.
+        /// They pay nothing on chain: block rewards go to the miner named in each
+        /// block's seal, and pools split them off chain based on the shares.
+        #[pallet::call_index(0)]
+        #[pallet::weight(Pallet::<T>::shares_weight(1))]
         #[transactional]
         pub fn submit_block(
             origin: OriginFor<T>,
             nonce: Vec<u8>,
             block_hash: T::Hash,
-        ) -> DispatchResultWithPostInfo {
+        ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            // Verify the proof of work
-            Self::verify_proof_of_work(&who, &nonce, block_hash)?;
+            // Work against old or unknown blocks could have been precomputed
+            let anchor = Self::ensure_recent_block_hash(block_hash)?;
 
-            // Record the mining reward
-            let reward = Self::calculate_block_reward();
-            <MiningRewards<T>>::mutate(&who, |rewards| {
-                *rewards = rewards.checked_add(&reward).ok_or(Error::<T>::ArithmeticOverflow)?;
-                Ok(())
-            })?;
+            // Verify the proof of work, and that it is new
+            let work = Self::verify_proof_of_work(&who, &nonce, block_hash)?;
+            Self::record_solved_work(anchor, work)?;
 
             // Emit event
-            Self::deposit_event(Event::BlockMined(who, block_hash, Self::current_difficulty()));
+            Self::deposit_event(Event::ShareSubmitted(
+                who,
+                block_hash,
+                Self::current_difficulty(),
+            ));
 
-            Ok(().into())
+            Ok(())
         }
 
-        /// Claim mining rewards
-        #[pallet::weight(10_000)]
+        /// Claim the mining rewards that have matured and vested
+        #[pallet::call_index(1)]
+        #[pallet::weight(Pallet::<T>::claim_weight())]
         #[transactional]
-        pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+        pub fn claim_rewards(origin: OriginFor<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            // Get the rewards
-            let rewards = <MiningRewards<T>>::get(&who);
+            // Take what each block's reward has released so far
+            let now = <frame_system::Pallet<T>>::block_number();
+            let rewards = <LockedRewards<T>>::mutate_exists(&who, |locked| {
+                let Some(rewards) = locked else {
+                    return Zero::zero();
+                };
+                let mut claimed = BalanceOf::<T>::zero();
+                for reward in rewards.iter_mut() {
+                    let claimable = Self::claimable(reward, now);
+                    reward.claimed = reward.claimed.saturating_add(claimable);
+                    claimed = claimed.saturating_add(claimable);
+                }
+                rewards.retain(|reward| !reward.is_spent());
+                if rewards.is_empty() {
+                    *locked = None;
+                }
+                claimed
+            });
             ensure!(!rewards.is_zero(), Error::<T>::NoRewardsToClaim);
 
             // Clear the rewards
-            <MiningRewards<T>>::remove(&who);
+            <MiningRewards<T>>::mutate_exists(&who, |total| {
+                let left = total.unwrap_or_default().saturating_sub(rewards);
+                *total = (!left.is_zero()).then_some(left);
+            });
 
-            // Transfer the rewards
-            T::Currency::deposit_creating(&who, rewards);
+            // Mint the rewards; if that fails (say, below the existential deposit),
+            // the whole claim is undone and the rewards stay claimable
+            let minted = T::Currency::mint_into(&who, rewards)?;
+            <Emission<T>>::mutate(|ledger| ledger.minted = ledger.minted.saturating_add(minted));
 
             // Emit event
             Self::deposit_event(Event::RewardsClaimed(who, rewards));
 
-            Ok(().into())
+            Ok(())
         }
 
-        /// Submit multiple mined blocks in a single transaction
-        #[pallet::weight(10_000)]
+        /// Submit multiple side-pool shares in a single transaction
+        #[pallet::call_index(2)]
+        #[pallet::weight(Pallet::<T>::shares_weight(blocks.len().saturated_into()))]
         #[transactional]
         pub fn submit_blocks(
             origin: OriginFor<T>,
             blocks: Vec<(Vec<u8>, T::Hash)>,
-        ) -> DispatchResultWithPostInfo {
+        ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             // Check that we're not submitting too many blocks
@@ -182,139 +428,314 @@ pub mod pallet {
                 Error::<T>::TooManyBlockClaims
             );
 
-            let mut total_reward = BalanceOf::<T>::zero();
-
-            // Verify each block
+            // Verify each share; recording it catches repeats later in the batch
             for (nonce, block_hash) in blocks {
-                // Verify the proof of work
-                Self::verify_proof_of_work(&who, &nonce, block_hash)?;
+                let anchor = Self::ensure_recent_block_hash(block_hash)?;
 
-                // Calculate the reward
-                let reward = Self::calculate_block_reward();
-                total_reward = total_reward
-                    .checked_add(&reward)
-                    .ok_or(Error::<T>::ArithmeticOverflow)?;
+                // Verify the proof of work, and that it is new
+                let work = Self::verify_proof_of_work(&who, &nonce, block_hash)?;
+                Self::record_solved_work(anchor, work)?;
 
                 // Emit event
-                Self::deposit_event(Event::BlockMined(who.clone(), block_hash, Self::current_difficulty()));
+                Self::deposit_event(Event::ShareSubmitted(
+                    who.clone(),
+                    block_hash,
+                    Self::current_difficulty(),
+                ));
             }
 
-            // Record the total mining reward
-            <MiningRewards<T>>::mutate(&who, |rewards| {
-                *rewards = rewards
-                    .checked_add(&total_reward)
-                    .ok_or(Error::<T>::ArithmeticOverflow)?;
-                Ok(())
-            })?;
-
-            Ok(().into())
+            Ok(())
         }
     }
 
     // Define the pallet's helper functions
     impl<T: Config> Pallet<T> {
-        /// Verify the proof of work
-        fn verify_proof_of_work(
+        /// Weight of submitting `shares` shares. Finding a share's block may read
+        /// every hash in the `RecentBlockWindow`; checking its work reads the block
+        /// number and the target, recording it `SolvedWork` and `SolvedWorkCount`,
+        /// and its event the target again.
+        pub fn shares_weight(shares: u32) -> Weight {
+            let window: u64 = T::RecentBlockWindow::get().into();
+            let share = T::DbWeight::get()
+                .reads_writes(window + 5, 3)
+                .saturating_add(Weight::from_parts(10_000, 0));
+            share.saturating_mul(shares.into())
+        }
+
+        /// Weight of `claim_rewards`: the block number, `LockedRewards`,
+        /// `MiningRewards`, the account and issuance it mints into, `Emission`, and
+        /// the event
+        pub fn claim_weight() -> Weight {
+            T::DbWeight::get()
+                .reads_writes(6, 6)
+                .saturating_add(Weight::from_parts(10_000, 0))
+        }
+
+        /// Expected hashes per proof of work at the current target
+        pub fn current_difficulty() -> u64 {
+            Self::current_target().difficulty()
+        }
+
+        /// Current target; a proof-of-work hash, read as a 256-bit big-endian
+        /// number, must not exceed it
+        pub fn target() -> U256 {
+            Self::current_target().as_u256()
+        }
+
+        /// Block at which `on_finalize` next adjusts the difficulty
+        pub fn next_retarget_block() -> BlockNumberFor<T> {
+            T::DifficultyAlgorithm::next_adjustment()
+        }
+
+        /// Checks the time of a block built on the current state, as an importing
+        /// node whose local time is in `data` (see `pow_primitives::time`)
+        pub fn check_block_time(now: u64, data: &InherentData) -> Result<(), InherentError> {
+            let local_time: u64 = data
+                .get_data(&time::INHERENT_IDENTIFIER)
+                .ok()
+                .flatten()
+                .ok_or(InherentError::MissingLocalTime)?;
+            time::check_future_drift(now, local_time, T::MaxFutureDrift::get())?;
+            time::check_median_time_past(now, &Self::recent_timestamps())
+        }
+
+        /// Remember the time of block `n`, keeping the last `MedianTimeSpan` times
+        fn record_block_time(n: BlockNumberFor<T>, now: u64) {
+            <BlockTimestamps<T>>::insert(n, now);
+            <RecentTimestamps<T>>::mutate(|recent| {
+                if recent.is_full() {
+                    recent.remove(0);
+                }
+                // Cannot fail: there is room after dropping the oldest
+                let _ = recent.try_push(now);
+            });
+        }
+
+        /// Ensure `block_hash` names one of the last `RecentBlockWindow` blocks, and
+        /// return that block's number
+        pub fn ensure_recent_block_hash(
+            block_hash: T::Hash,
+        ) -> Result<BlockNumberFor<T>, Error<T>> {
+            // Missing entries read as the default hash
+            ensure!(block_hash != T::Hash::default(), Error::<T>::StaleBlockHash);
+
+            let mut number = <frame_system::Pallet<T>>::block_number();
+            let oldest = number.saturating_sub(T::RecentBlockWindow::get().into());
+            while number > oldest {
+                number -= One::one();
+                if <frame_system::Pallet<T>>::block_hash(number) == block_hash {
+                    return Ok(number);
+                }
+            }
+            Err(Error::<T>::StaleBlockHash)
+        }
+
+        /// Verify the proof of work and return its hash
+        pub fn verify_proof_of_work(
             who: &T::AccountId,
             nonce: &[u8],
             block_hash: T::Hash,
-        ) -> Result<(), Error<T>> {
+        ) -> Result<T::Hash, Error<T>> {
             // Combine the account ID, nonce, and block hash
             let mut data = who.encode();
             data.extend_from_slice(nonce);
             data.extend_from_slice(&block_hash.encode());
 
-        // Hash the data using the runtime's configured HASHING (Blake3)
-        let header_hash = <T as frame_system::Config>::Hashing::hash(&data);
-        let hash_bytes = header_hash.as_ref();
-        // Convert the first 8 bytes to a u64 for difficulty comparison
-        let hash_value = u64::from_be_bytes(hash_bytes[0..8].try_into().unwrap());
+            // Hash the data using the runtime's configured HASHING (Blake3)
+            let header_hash = <T as frame_system::Config>::Hashing::hash(&data);
 
-            // Check if the hash meets the difficulty target
-            let difficulty = Self::current_difficulty();
-            let target = u64::MAX / difficulty;
-
-            if hash_value <= target {
-                Ok(())
+            // Check if the whole hash meets the target
+            if Self::current_target().is_met_by(header_hash.as_ref()) {
+                Ok(header_hash)
             } else {
                 Err(Error::<T>::DifficultyTargetNotMet)
             }
         }
 
-        /// Calculate the block reward
-        fn calculate_block_reward() -> BalanceOf<T> {
-            // In a real implementation, this would include a halving schedule
-            // and potentially other factors
-            BalanceOf::<T>::from(500_000_000_000u64)
+        /// Remember `work`, mined against block `anchor`, rejecting it if it was
+        /// seen before
+        fn record_solved_work(anchor: BlockNumberFor<T>, work: T::Hash) -> Result<(), Error<T>> {
+            ensure!(
+                !<SolvedWork<T>>::contains_key(anchor, work),
+                Error::<T>::DuplicateWork
+            );
+            <SolvedWorkCount<T>>::try_mutate(anchor, |count| {
+                ensure!(
+                    *count < T::MaxSharesPerBlock::get(),
+                    Error::<T>::TooManyShares
+                );
+                *count += 1;
+                Ok::<_, Error<T>>(())
+            })?;
+            <SolvedWork<T>>::insert(anchor, work, ());
+            Ok(())
         }
 
-        /// Adjust the mining difficulty
-        fn adjust_difficulty(current_block: T::BlockNumber) {
-            let adjustment_period = T::DifficultyAdjustmentPeriod::get();
-            let last_adjustment = Self::last_difficulty_adjustment();
-            
-            if current_block <= last_adjustment || (current_block - last_adjustment) < adjustment_period {
+        /// Forget the work mined against the block that leaves the
+        /// `RecentBlockWindow` once block `n` is final
+        fn prune_solved_work(n: BlockNumberFor<T>) {
+            let window = T::RecentBlockWindow::get().into();
+            if n < window {
                 return;
             }
+            let expired = n - window;
+            let count = <SolvedWorkCount<T>>::take(expired);
+            if count > 0 {
+                // `count` covers every entry, so one pass clears them all
+                let _ = <SolvedWork<T>>::clear_prefix(expired, count, None);
+            }
+        }
 
-            // Get the timestamps of the first and last blocks in the period
-            let first_block_time = <BlockTimestamps<T>>::get(last_adjustment);
-            let last_block_time = <BlockTimestamps<T>>::get(current_block);
+        /// Credit the reward of block `number` to its miner; claimed later through
+        /// `claim_rewards`
+        fn reward_block_author(
+            miner: T::AccountId,
+            number: BlockNumberFor<T>,
+            block_hash: T::Hash,
+            difficulty: u64,
+        ) {
+            let reward = Self::block_reward(number);
+            <TotalMinted<T>>::mutate(|minted| *minted = minted.saturating_add(reward));
+            <MiningRewards<T>>::mutate(&miner, |rewards| *rewards = rewards.saturating_add(reward));
+            <LockedRewards<T>>::mutate(&miner, |locked| {
+                let lock = LockedReward {
+                    mined_at: number,
+                    amount: reward,
+                    claimed: Zero::zero(),
+                };
+                if let Err(lock) = locked.try_push(lock) {
+                    // Full: relock the newest with this one, which only delays it
+                    if let Some(newest) = locked.last_mut() {
+                        newest.mined_at = lock.mined_at;
+                        newest.amount = newest.amount.saturating_add(lock.amount);
+                    }
+                }
+            });
+            Self::deposit_event(Event::BlockMined(miner, block_hash, difficulty));
+        }
 
-            // Calculate the actual time it took to mine these blocks
-            let actual_time_taken = last_block_time.saturating_sub(first_block_time);
-            
-            // Calculate the target time
-            let target_time = T::TargetBlockTime::get()
-                .saturating_mul(adjustment_period.saturated_into::<u64>());
+        /// Check that the rewards minted into the currency, as `Emission` records
+        /// them, and those still unclaimed add up to the rewards credited so far
+        ///
+        /// The total issuance itself is not compared with the ledger: root calls
+        /// such as `force_set_balance` change it without passing the ledger, which
+        /// only records `claim_rewards` and `rewards::BurnToLedger`.
+        pub fn check_emission_ledger() -> Result<(), &'static str> {
+            let unclaimed = <MiningRewards<T>>::iter_values()
+                .fold(BalanceOf::<T>::zero(), |total, rewards| {
+                    total.saturating_add(rewards)
+                });
+            if Self::emission().minted.saturating_add(unclaimed) != Self::total_minted() {
+                return Err("Minted and unclaimed rewards differ from the rewards credited");
+            }
+            Ok(())
+        }
 
-            // Adjust difficulty based on the ratio of actual to target time
-            let current_difficulty = Self::current_difficulty();
-            let new_difficulty = if actual_time_taken < target_time {
-                // Blocks are being mined too quickly, increase difficulty
-                let adjustment_factor = target_time as f64 / actual_time_taken as f64;
-                let new_diff = (current_difficulty as f64 * adjustment_factor) as u64;
-                new_diff.max(1) // Ensure difficulty is at least 1
-            } else {
-                // Blocks are being mined too slowly, decrease difficulty
-                let adjustment_factor = actual_time_taken as f64 / target_time as f64;
-                let new_diff = (current_difficulty as f64 / adjustment_factor) as u64;
-                new_diff.max(1) // Ensure difficulty is at least 1
-            };
+        /// Rewards `who` could claim now
+        pub fn matured_rewards(who: &T::AccountId) -> BalanceOf<T> {
+            let now = <frame_system::Pallet<T>>::block_number();
+            Self::locked_rewards(who)
+                .iter()
+                .fold(Zero::zero(), |total: BalanceOf<T>, reward| {
+                    total.saturating_add(Self::claimable(reward, now))
+                })
+        }
 
-            // Update the difficulty and last adjustment block
-            <CurrentDifficulty<T>>::put(new_difficulty);
-            <LastDifficultyAdjustment<T>>::put(current_block);
+        /// Part of `reward` that can be claimed at block `now`
+        fn claimable(
+            reward: &LockedReward<BlockNumberFor<T>, BalanceOf<T>>,
+            now: BlockNumberFor<T>,
+        ) -> BalanceOf<T> {
+            reward.claimable(
+                now,
+                T::CoinbaseMaturity::get(),
+                T::RewardVestingPeriod::get(),
+            )
+        }
 
-            // Emit event
-            Self::deposit_event(Event::DifficultyAdjusted(current_difficulty, new_difficulty));
+        /// Reward of the next block to be mined
+        pub fn calculate_block_reward() -> BalanceOf<T> {
+            Self::block_reward(<frame_system::Pallet<T>>::block_number() + One::one())
+        }
+
+        /// Reward of block `height` after the rewards credited so far
+        pub fn block_reward(height: BlockNumberFor<T>) -> BalanceOf<T> {
+            Self::reward_schedule()
+                .block_reward(
+                    height.saturated_into(),
+                    Self::total_minted().saturated_into(),
+                )
+                .saturated_into()
+        }
+
+        /// The configured reward schedule, in `coin-tokenomics` terms
+        pub fn reward_schedule() -> RewardSchedule {
+            RewardSchedule {
+                initial_reward: T::InitialBlockReward::get().saturated_into(),
+                halving_interval: T::HalvingInterval::get().saturated_into(),
+                tail_emission: T::TailEmission::get().saturated_into(),
+                max_supply: T::MaxSupply::get().saturated_into(),
+            }
+        }
+
+        /// Let the difficulty algorithm set the difficulty of the block after `n`
+        fn adjust_difficulty(n: BlockNumberFor<T>) {
+            let Some(new_difficulty) = T::DifficultyAlgorithm::next_difficulty(n) else {
+                return;
+            };
+            let current_difficulty = Self::current_difficulty();
+            // Algorithms never go below 1; treat a 0 from a custom one as 1 too
+            <CurrentTarget<T>>::put(Target::from_difficulty(new_difficulty).unwrap_or_default());
+            <LastDifficultyAdjustment<T>>::put(n);
+
+            if new_difficulty != current_difficulty {
+                Self::deposit_event(Event::DifficultyAdjusted(
+                    current_difficulty,
+                    new_difficulty,
+                ));
+            }
         }
     }
 
     // Define the pallet's genesis configuration
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
+        /// Expected hashes per block at genesis; zero is rejected
         pub initial_difficulty: u64,
+        /// Keep `initial_difficulty` forever instead of retargeting
+        pub fixed_difficulty: bool,
+        #[serde(skip)]
+        pub _config: PhantomData<T>,
     }
 
-    #[cfg(feature = "std")]
     impl<T: Config> Default for GenesisConfig<T> {
         fn default() -> Self {
             Self {
                 initial_difficulty: 1_000_000, // Default initial difficulty
+                fixed_difficulty: false,
+                _config: PhantomData,
             }
         }
     }
 
     #[pallet::genesis_build]
-    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
         fn build(&self) {
-            <CurrentDifficulty<T>>::put(self.initial_difficulty);
-            <LastDifficultyAdjustment<T>>::put(T::BlockNumber::zero());
+            let target = Target::from_difficulty(self.initial_difficulty)
+                .expect("The initial difficulty must not be zero");
+            <CurrentTarget<T>>::put(target);
+            <FixedDifficulty<T>>::put(self.fixed_difficulty);
+            <LastDifficultyAdjustment<T>>::put(BlockNumberFor::<T>::zero());
+            <ActiveDifficultyAlgorithm<T>>::put(T::DifficultyAlgorithm::kind());
+            // Balances built their genesis before us
+            <Emission<T>>::put(EmissionLedger {
+                genesis: T::Currency::total_issuance(),
+                ..Default::default()
+            });
         }
     }
 
     // Define the pallet's types
-    type BalanceOf<T> =
-        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type BalanceOf<T> =
+        <<T as Config>::Currency as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 }
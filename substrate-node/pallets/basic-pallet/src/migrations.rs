@@ -0,0 +1,157 @@
+//! Storage migrations of the mining pallet.
+
+use frame_support::{
+    pallet_prelude::*,
+    storage_alias,
+    traits::{fungible::Inspect, GetStorageVersion, OnRuntimeUpgrade},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pow_primitives::target::Target;
+use sp_runtime::traits::{Saturating, Zero};
+use sp_std::{marker::PhantomData, prelude::*};
+
+use crate::{
+    difficulty::DifficultyAlgorithm,
+    rewards::{EmissionLedger, LockedReward},
+    ActiveDifficultyAlgorithm, AsertAnchor, BalanceOf, BlockDifficulties, BlockTimestamps, Config,
+    CurrentTarget, Emission, Event, LastDifficultyAdjustment, LockedRewards, MiningRewards, Pallet,
+    TotalMinted,
+};
+
+/// Storage as of version 0.
+mod v0 {
+    use super::*;
+
+    #[storage_alias]
+    pub type CurrentDifficulty<T: Config> = StorageValue<Pallet<T>, u64, OptionQuery>;
+}
+
+/// Replaces the `u64` difficulty of storage version 0 with the equivalent `Target`
+/// and bumps the storage version to 1; does nothing on later versions.
+///
+/// Version 0 treated a difficulty of 0 as 1, and so does the migration.
+pub struct DifficultyToTarget<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for DifficultyToTarget<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() >= 1 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let target = v0::CurrentDifficulty::<T>::take()
+            .and_then(Target::from_difficulty)
+            .unwrap_or_default();
+        <CurrentTarget<T>>::put(target);
+        StorageVersion::new(1).put::<Pallet<T>>();
+
+        T::DbWeight::get().reads_writes(2, 3)
+    }
+}
+
+/// Locks the unclaimed rewards of storage version 1, which were claimable at once,
+/// as a single reward mined at genesis, and bumps the storage version to 2; does
+/// nothing on later versions.
+///
+/// They are claimable as soon as the chain is `CoinbaseMaturity` plus
+/// `RewardVestingPeriod` blocks old.
+pub struct LockRewardsPerBlock<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for LockRewardsPerBlock<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() >= 2 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut accounts = 0u64;
+        for (who, amount) in <MiningRewards<T>>::iter() {
+            let reward = LockedReward {
+                mined_at: Zero::zero(),
+                amount,
+                claimed: Zero::zero(),
+            };
+            // Cannot fail: `integrity_test` requires room for one
+            if let Ok(rewards) = BoundedVec::try_from(vec![reward]) {
+                <LockedRewards<T>>::insert(who, rewards);
+            }
+            accounts += 1;
+        }
+        StorageVersion::new(2).put::<Pallet<T>>();
+
+        T::DbWeight::get().reads_writes(1 + accounts, 1 + accounts)
+    }
+}
+
+/// Starts the emission ledger of storage version 3 and bumps the storage version;
+/// does nothing on later versions.
+///
+/// Rewards credited and not claimed yet were never minted, so the rest of
+/// `TotalMinted` is what claims minted. Whatever else was issued, including dust
+/// burned before the ledger existed, is counted as genesis issuance.
+pub struct StartEmissionLedger<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for StartEmissionLedger<T> {
+    fn on_runtime_upgrade() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() >= 3 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut accounts = 0u64;
+        let unclaimed =
+            <MiningRewards<T>>::iter_values().fold(BalanceOf::<T>::zero(), |total, rewards| {
+                accounts += 1;
+                total.saturating_add(rewards)
+            });
+        let minted = <TotalMinted<T>>::get().saturating_sub(unclaimed);
+        let issuance = T::Currency::total_issuance();
+        <Emission<T>>::put(EmissionLedger {
+            genesis: issuance.saturating_sub(minted),
+            minted,
+            burned: Zero::zero(),
+        });
+        StorageVersion::new(3).put::<Pallet<T>>();
+
+        T::DbWeight::get().reads_writes(3 + accounts, 2)
+    }
+}
+
+/// Hands the difficulty over to `T::DifficultyAlgorithm` after a runtime upgrade
+/// that changed it; does nothing otherwise, so it can stay in the runtime's
+/// migrations for good.
+///
+/// Upgrades run before the first block of the new runtime is initialized, while the
+/// block number is still the parent's, and the new algorithm starts from there: a
+/// periodic window begins at the parent, LWMA reuses the recorded blocks inside its
+/// window, and ASERT anchors at the first block it sees.
+/// The current difficulty carries over unchanged.
+pub struct SwitchDifficultyAlgorithm<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for SwitchDifficultyAlgorithm<T> {
+    fn on_runtime_upgrade() -> Weight {
+        let from = <ActiveDifficultyAlgorithm<T>>::get();
+        let to = T::DifficultyAlgorithm::kind();
+        if from == to {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let parent = frame_system::Pallet::<T>::block_number();
+        <LastDifficultyAdjustment<T>>::put(parent);
+        <AsertAnchor<T>>::kill();
+
+        // Drop the records the previous algorithm kept that the new one never reads
+        let history: BlockNumberFor<T> = T::DifficultyAlgorithm::history().into();
+        let keep_from = parent.saturating_sub(history);
+        let stale: Vec<_> = <BlockTimestamps<T>>::iter_keys()
+            .filter(|number| *number < keep_from)
+            .collect();
+        for number in &stale {
+            <BlockTimestamps<T>>::remove(number);
+            <BlockDifficulties<T>>::remove(number);
+        }
+        let stale = stale.len() as u64;
+
+        <ActiveDifficultyAlgorithm<T>>::put(to);
+        Pallet::<T>::deposit_event(Event::DifficultyAlgorithmSwitched(from, to));
+
+        T::DbWeight::get().reads_writes(2 + stale, 4 + 2 * stale)
+    }
+}
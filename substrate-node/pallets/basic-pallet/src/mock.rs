@@ -0,0 +1,183 @@
+//! Test runtime around the mining pallet, with small rewards and maturity so that
+//! the pallet's own logic can be checked without the full runtime.
+
+use core::cell::Cell;
+
+use frame_support::{
+    construct_runtime,
+    traits::{ConstU32, ConstU64, Everything, FindAuthor, Hooks, Randomness, UnixTime},
+    weights::constants::RocksDbWeight,
+};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage, ConsensusEngineId,
+};
+
+use crate::{self as pallet_basic_pallet, difficulty::Periodic, rewards::BurnToLedger};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type BlockNumber = u64;
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+/// Reward of every block
+pub const REWARD: Balance = 1_000;
+/// Blocks a reward stays locked for
+pub const MATURITY: BlockNumber = 10;
+/// Blocks between retargets
+pub const PERIOD: BlockNumber = 10;
+/// Block time the difficulty aims for, in milliseconds
+pub const BLOCK_TIME: u64 = 1_000;
+pub const EXISTENTIAL_DEPOSIT: Balance = 10;
+
+pub const MINER: AccountId = 1;
+pub const ALICE: AccountId = 2;
+
+construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Mining: pallet_basic_pallet,
+    }
+);
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = RocksDbWeight;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+impl pallet_balances::Config for Test {
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = BurnToLedger<Test>;
+    type ExistentialDeposit = ConstU64<EXISTENTIAL_DEPOSIT>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type MaxLocks = ConstU32<0>;
+    type MaxReserves = ConstU32<0>;
+    type ReserveIdentifier = ();
+    type FreezeIdentifier = ();
+    type MaxFreezes = ConstU32<0>;
+    type RuntimeHoldReason = ();
+    type RuntimeFreezeReason = ();
+    type MaxHolds = ConstU32<0>;
+}
+
+impl pallet_basic_pallet::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type Randomness = NoRandomness;
+    type FindAuthor = Author;
+    type TimeProvider = Clock;
+    type MaxBlockClaims = ConstU32<4>;
+    type RecentBlockWindow = ConstU32<16>;
+    type MaxSharesPerBlock = ConstU32<4>;
+    type InitialBlockReward = ConstU64<REWARD>;
+    type HalvingInterval = ConstU64<0>;
+    type TailEmission = ConstU64<0>;
+    type MaxSupply = ConstU64<{ u64::MAX }>;
+    type CoinbaseMaturity = ConstU64<MATURITY>;
+    type RewardVestingPeriod = ConstU64<0>;
+    type MaxLockedRewards = ConstU32<3>;
+    type DifficultyAlgorithm = Periodic<ConstU64<PERIOD>>;
+    type TargetBlockTime = ConstU64<BLOCK_TIME>;
+    type MedianTimeSpan = ConstU32<3>;
+    type MaxFutureDrift = ConstU64<BLOCK_TIME>;
+}
+
+thread_local! {
+    static AUTHOR: Cell<Option<AccountId>> = const { Cell::new(None) };
+    static NOW: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Miner of the blocks being built, set with `set_author` in place of a pre-runtime
+/// digest.
+pub struct Author;
+
+impl FindAuthor<AccountId> for Author {
+    fn find_author<'a, I>(_digests: I) -> Option<AccountId>
+    where
+        I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+    {
+        AUTHOR.with(Cell::get)
+    }
+}
+
+pub fn set_author(author: Option<AccountId>) {
+    AUTHOR.with(|cell| cell.set(author));
+}
+
+/// Time of the block being built, in place of the timestamp inherent.
+pub struct Clock;
+
+impl UnixTime for Clock {
+    fn now() -> core::time::Duration {
+        core::time::Duration::from_millis(NOW.with(Cell::get))
+    }
+}
+
+pub struct NoRandomness;
+
+impl Randomness<H256, BlockNumber> for NoRandomness {
+    fn random(_subject: &[u8]) -> (H256, BlockNumber) {
+        (H256::zero(), 0)
+    }
+}
+
+/// A chain at block 1 endowing `ALICE` with 1,000,000, at difficulty 1 and retargeting
+/// unless `fixed_difficulty`.
+pub fn new_test_ext(fixed_difficulty: bool) -> sp_io::TestExternalities {
+    set_author(None);
+    NOW.with(|cell| cell.set(0));
+    let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig {
+        balances: pallet_balances::GenesisConfig {
+            balances: vec![(ALICE, 1_000_000)],
+        },
+        mining: pallet_basic_pallet::GenesisConfig {
+            initial_difficulty: 1,
+            fixed_difficulty,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+    .build_storage()
+    .expect("the genesis config is valid")
+    .into();
+    // Events are only recorded from block 1 on, which also starts the first window
+    ext.execute_with(|| run_to_block(1));
+    ext
+}
+
+/// Runs block `number` on top of the current state, `BLOCK_TIME` after the last.
+pub fn run_to_block(number: BlockNumber) {
+    while System::block_number() < number {
+        let next = System::block_number() + 1;
+        System::set_block_number(next);
+        Mining::on_initialize(next);
+        NOW.with(|cell| cell.set(next * BLOCK_TIME));
+        Mining::on_finalize(next);
+    }
+}
@@ -0,0 +1,109 @@
+//! Coinbase maturity and vesting.
+//!
+//! A block's reward is credited to its miner in the next block, but stays locked
+//! for `Config::CoinbaseMaturity` blocks after the block it was mined in, so a
+//! reward whose block is lost to a reorg was never spendable. It is then released
+//! linearly over `Config::RewardVestingPeriod` blocks, or all at once if that is
+//! zero.
+//!
+//! Claimed rewards are minted, and `EmissionLedger` keeps track of how the total
+//! issuance came about.
+
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Imbalance, OnUnbalanced},
+};
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational_with_rounding,
+    traits::{AtLeast32BitUnsigned, SaturatedConversion, Saturating, Zero},
+    Rounding,
+};
+use sp_std::marker::PhantomData;
+
+use crate::{BalanceOf, Config, Emission};
+
+/// Reward of one mined block, and how much of it was claimed.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug)]
+pub struct LockedReward<BlockNumber, Balance> {
+    /// Block the reward was earned in; maturity counts from here
+    pub mined_at: BlockNumber,
+    pub amount: Balance,
+    pub claimed: Balance,
+}
+
+impl<BlockNumber, Balance> LockedReward<BlockNumber, Balance>
+where
+    BlockNumber: AtLeast32BitUnsigned + Copy,
+    Balance: AtLeast32BitUnsigned + Copy,
+{
+    /// Part of the reward released by block `now`, claimed or not.
+    pub fn released(
+        &self,
+        now: BlockNumber,
+        maturity: BlockNumber,
+        vesting: BlockNumber,
+    ) -> Balance {
+        let matured_at = self.mined_at.saturating_add(maturity);
+        if now < matured_at {
+            return Zero::zero();
+        }
+        let vested = now - matured_at;
+        if vested >= vesting {
+            return self.amount;
+        }
+        multiply_by_rational_with_rounding(
+            self.amount.saturated_into(),
+            vested.saturated_into(),
+            vesting.saturated_into(),
+            Rounding::Down,
+        )
+        .map_or(self.amount, |released| released.saturated_into())
+    }
+
+    /// Part of the reward released by block `now` and not claimed yet.
+    pub fn claimable(
+        &self,
+        now: BlockNumber,
+        maturity: BlockNumber,
+        vesting: BlockNumber,
+    ) -> Balance {
+        self.released(now, maturity, vesting)
+            .saturating_sub(self.claimed)
+    }
+
+    /// Whether all of the reward was claimed.
+    pub fn is_spent(&self) -> bool {
+        self.claimed >= self.amount
+    }
+}
+
+/// Where the currency's total issuance came from: what was there at genesis, plus
+/// the rewards minted, minus what was burned since.
+#[derive(
+    Clone, Copy, Default, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo, RuntimeDebug,
+)]
+pub struct EmissionLedger<Balance> {
+    pub genesis: Balance,
+    pub minted: Balance,
+    pub burned: Balance,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy> EmissionLedger<Balance> {
+    /// Total issuance the ledger accounts for.
+    pub fn issuance(&self) -> Balance {
+        self.genesis
+            .saturating_add(self.minted)
+            .saturating_sub(self.burned)
+    }
+}
+
+/// Drops an imbalance, such as the dust of a reaped account, and records the burn
+/// in the emission ledger. Meant for the currency's `DustRemoval`.
+pub struct BurnToLedger<T>(PhantomData<T>);
+
+impl<T: Config, I: Imbalance<BalanceOf<T>>> OnUnbalanced<I> for BurnToLedger<T> {
+    fn on_nonzero_unbalanced(amount: I) {
+        let burned = amount.peek();
+        <Emission<T>>::mutate(|ledger| ledger.burned = ledger.burned.saturating_add(burned));
+    }
+}
@@ -0,0 +1,234 @@
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::GetDispatchInfo,
+    traits::{Get, Hooks},
+    weights::{constants::RocksDbWeight, RuntimeDbWeight, Weight},
+    BoundedVec,
+};
+use sp_runtime::TokenError;
+
+use crate::{
+    mock::*,
+    rewards::{EmissionLedger, LockedReward},
+    BlockDifficulties, BlockTimestamps, Error, LockedRewards, MiningRewards, TotalMinted,
+};
+
+fn claim() -> frame_support::dispatch::DispatchResult {
+    Mining::claim_rewards(RuntimeOrigin::signed(MINER))
+}
+
+/// Mines block `number` as `MINER`; its reward is credited in the next block.
+fn mine(number: BlockNumber) {
+    set_author(Some(MINER));
+    run_to_block(number);
+    set_author(None);
+    run_to_block(number + 1);
+}
+
+#[test]
+fn reward_is_released_at_maturity_and_vests_linearly() {
+    let reward = LockedReward {
+        mined_at: 10u32,
+        amount: 1_000u128,
+        claimed: 0,
+    };
+    let released = |now| reward.released(now, 100, 50);
+    assert_eq!(released(109), 0);
+    assert_eq!(released(110), 0);
+    assert_eq!(released(135), 500);
+    assert_eq!(released(159), 980);
+    assert_eq!(released(160), 1_000);
+    assert_eq!(released(u32::MAX), 1_000);
+    // Without vesting, all of it at maturity
+    assert_eq!(reward.released(110, 100, 0), 1_000);
+
+    let partly_claimed = LockedReward {
+        claimed: 300,
+        ..reward
+    };
+    assert_eq!(partly_claimed.claimable(135, 100, 50), 200);
+    assert_eq!(partly_claimed.claimable(120, 100, 50), 0);
+    assert!(!partly_claimed.is_spent());
+}
+
+#[test]
+fn ledger_issuance_is_genesis_plus_minted_minus_burned() {
+    let ledger = EmissionLedger {
+        genesis: 100u64,
+        minted: 30,
+        burned: 5,
+    };
+    assert_eq!(ledger.issuance(), 125);
+}
+
+#[test]
+fn locked_reward_is_claimable_once_its_block_matures() {
+    new_test_ext(false).execute_with(|| {
+        mine(2);
+        assert_eq!(Mining::mining_rewards(MINER), REWARD);
+        assert_eq!(Mining::matured_rewards(&MINER), 0);
+
+        run_to_block(2 + MATURITY - 1);
+        assert_noop!(claim(), Error::<Test>::NoRewardsToClaim);
+
+        run_to_block(2 + MATURITY);
+        assert_ok!(claim());
+        assert_eq!(Balances::free_balance(MINER), REWARD);
+        assert_eq!(Mining::mining_rewards(MINER), 0);
+        assert!(!LockedRewards::<Test>::contains_key(MINER));
+        assert_eq!(Mining::emission().minted, REWARD);
+        assert_ok!(Mining::check_emission_ledger());
+    });
+}
+
+#[test]
+fn locked_rewards_beyond_the_bound_join_the_newest() {
+    new_test_ext(false).execute_with(|| {
+        let max: u32 = <Test as crate::Config>::MaxLockedRewards::get();
+        set_author(Some(MINER));
+        // Blocks 2 to max + 2 are credited by the end of block max + 3
+        run_to_block(max as BlockNumber + 3);
+
+        let locked = LockedRewards::<Test>::get(MINER);
+        assert_eq!(locked.len() as u32, max);
+        let newest = locked.last().unwrap();
+        assert_eq!(newest.mined_at, max as BlockNumber + 2);
+        assert_eq!(newest.amount, 2 * REWARD);
+        let total: Balance = locked.iter().map(|reward| reward.amount).sum();
+        assert_eq!(total, MiningRewards::<Test>::get(MINER));
+    });
+}
+
+#[test]
+fn claim_that_cannot_be_minted_keeps_the_rewards() {
+    new_test_ext(false).execute_with(|| {
+        // Too little to create the miner's account
+        let dust = EXISTENTIAL_DEPOSIT - 1;
+        MiningRewards::<Test>::insert(MINER, dust);
+        TotalMinted::<Test>::put(dust);
+        LockedRewards::<Test>::insert(
+            MINER,
+            BoundedVec::truncate_from(vec![LockedReward {
+                mined_at: 0,
+                amount: dust,
+                claimed: 0,
+            }]),
+        );
+        run_to_block(MATURITY);
+
+        assert_noop!(claim(), TokenError::BelowMinimum);
+        assert_eq!(Mining::matured_rewards(&MINER), dust);
+        assert_ok!(Mining::check_emission_ledger());
+    });
+}
+
+#[test]
+fn burned_dust_is_on_the_ledger() {
+    new_test_ext(false).execute_with(|| {
+        let dust = EXISTENTIAL_DEPOSIT - 1;
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(ALICE),
+            MINER,
+            1_000_000 - dust
+        ));
+        assert_eq!(Balances::total_issuance(), 1_000_000 - dust);
+        assert_eq!(Mining::emission().burned, dust);
+        assert_ok!(Mining::check_emission_ledger());
+    });
+}
+
+#[test]
+fn root_balance_changes_are_not_on_the_ledger() {
+    new_test_ext(false).execute_with(|| {
+        assert_ok!(Balances::force_set_balance(
+            RuntimeOrigin::root(),
+            ALICE,
+            2_000_000
+        ));
+        assert_eq!(Mining::emission().issuance(), 1_000_000);
+        assert_eq!(Balances::total_issuance(), 2_000_000);
+        assert_ok!(Mining::check_emission_ledger());
+    });
+}
+
+#[cfg(feature = "try-runtime")]
+#[test]
+fn try_state_checks_the_emission_ledger() {
+    new_test_ext(false).execute_with(|| {
+        mine(2);
+        run_to_block(2 + MATURITY);
+        assert_ok!(claim());
+        assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), ALICE, 0));
+        let now = System::block_number();
+        assert_ok!(Mining::try_state(now));
+
+        // A reward credited without being minted or left to claim
+        TotalMinted::<Test>::mutate(|minted| *minted += 1);
+        assert!(Mining::try_state(now).is_err());
+    });
+}
+
+#[test]
+fn block_history_stays_bounded_with_a_fixed_difficulty() {
+    new_test_ext(true).execute_with(|| {
+        run_to_block(10 * PERIOD);
+        assert_eq!(Mining::last_difficulty_adjustment(), 0);
+        // The periodic algorithm reads the last `PERIOD` blocks besides the current one
+        let bound = PERIOD as usize + 1;
+        assert!(BlockTimestamps::<Test>::iter().count() <= bound);
+        assert!(BlockDifficulties::<Test>::iter().count() <= bound);
+        assert!(!BlockTimestamps::<Test>::contains_key(9 * PERIOD - 1));
+    });
+}
+
+#[test]
+fn block_history_stays_bounded_while_retargeting() {
+    new_test_ext(false).execute_with(|| {
+        run_to_block(10 * PERIOD + PERIOD / 2);
+        assert_eq!(Mining::last_difficulty_adjustment(), 10 * PERIOD);
+        let bound = PERIOD as usize + 1;
+        assert!(BlockTimestamps::<Test>::iter().count() <= bound);
+        assert!(BlockDifficulties::<Test>::iter().count() <= bound);
+    });
+}
+
+#[test]
+fn on_initialize_weighs_the_coinbase() {
+    new_test_ext(false).execute_with(|| {
+        let db: RuntimeDbWeight = RocksDbWeight::get();
+
+        // Nothing to pay, nobody to remember
+        System::set_block_number(2);
+        assert_eq!(Mining::on_initialize(2), db.reads_writes(2, 1));
+
+        // Remembering this block's author
+        set_author(Some(MINER));
+        System::set_block_number(3);
+        assert_eq!(Mining::on_initialize(3), db.reads_writes(3, 2));
+
+        // Paying the parent's author, too
+        System::set_block_number(4);
+        assert_eq!(Mining::on_initialize(4), db.reads_writes(7, 6));
+        assert_eq!(Mining::mining_rewards(MINER), REWARD);
+    });
+}
+
+#[test]
+fn shares_weigh_every_recent_block_hash_and_the_replay_record() {
+    let db: RuntimeDbWeight = RocksDbWeight::get();
+    let window: u32 = <Test as crate::Config>::RecentBlockWindow::get();
+    let share = db
+        .reads_writes(window as u64 + 5, 3)
+        .saturating_add(Weight::from_parts(10_000, 0));
+
+    let single = crate::Call::<Test>::submit_block {
+        nonce: vec![],
+        block_hash: Default::default(),
+    };
+    assert_eq!(single.get_dispatch_info().weight, share);
+
+    let batch = crate::Call::<Test>::submit_blocks {
+        blocks: vec![(vec![], Default::default()); 3],
+    };
+    assert_eq!(batch.get_dispatch_info().weight, share.saturating_mul(3));
+}
@@ -0,0 +1,117 @@
+//! Difficulty retargeting in integer arithmetic.
+//!
+//! Retargets are part of consensus, so every node must compute bit-identical results
+//! whether the runtime runs natively or in Wasm. Floating point is out; ratios of
+//! target to actual time are applied as exact 256-bit products and quotients.
+//!
+//! Three rules are provided: [`retarget`] for a fixed window of blocks, [`lwma`]
+//! recomputed every block from a moving window, and [`asert`] recomputed every block
+//! against an anchor block.
+
+use primitive_types::U256;
+
+/// Largest factor by which a single retarget may raise or lower the difficulty.
+pub const MAX_ADJUSTMENT_FACTOR: u64 = 4;
+
+/// Difficulty after a window that was meant to take `target_time` but took
+/// `actual_time` (both in milliseconds).
+///
+/// The result is `difficulty * target_time / actual_time` rounded down, clamped to
+/// `[difficulty / 4, difficulty * 4]` (bounds rounded down and saturating at
+/// `u64::MAX`), and never below 1. A window that took no time at all counts as
+/// arbitrarily fast and raises the difficulty by the full factor.
+pub fn retarget(difficulty: u64, actual_time: u64, target_time: u64) -> u64 {
+    let difficulty = difficulty.max(1);
+    let lowest = (difficulty / MAX_ADJUSTMENT_FACTOR).max(1);
+    let highest = difficulty.saturating_mul(MAX_ADJUSTMENT_FACTOR);
+    if actual_time == 0 {
+        return highest;
+    }
+    let scaled = U256::from(difficulty) * U256::from(target_time) / U256::from(actual_time);
+    if scaled > U256::from(highest) {
+        highest
+    } else {
+        scaled.low_u64().max(lowest)
+    }
+}
+
+/// Solve times above this many target block times count as this many, so a single
+/// stalled block cannot collapse the difficulty.
+pub const LWMA_MAX_SOLVE_TIME_FACTOR: u64 = 6;
+
+/// Linearly weighted moving average (LWMA-1) difficulty for the next block.
+///
+/// `blocks` holds `(timestamp, difficulty)` of consecutive blocks, oldest first. The
+/// solve times between them are weighted 1 for the oldest up to N for the newest,
+/// each limited to `[1, 6 * target_time]`, and the average difficulty of the last N
+/// blocks is scaled by target over weighted solve time, rounded down. `None` until
+/// there are at least two blocks.
+pub fn lwma(blocks: &[(u64, u64)], target_time: u64) -> Option<u64> {
+    let samples = blocks.len().checked_sub(1).filter(|&n| n > 0)? as u64;
+    let max_solve_time = target_time
+        .saturating_mul(LWMA_MAX_SOLVE_TIME_FACTOR)
+        .max(1);
+
+    let mut weighted_solve_times = U256::zero();
+    let mut difficulties = U256::zero();
+    for (weight, pair) in (1u64..).zip(blocks.windows(2)) {
+        let [(previous, _), (timestamp, difficulty)] = [pair[0], pair[1]];
+        let solve_time = timestamp.saturating_sub(previous).clamp(1, max_solve_time);
+        weighted_solve_times += U256::from(weight) * U256::from(solve_time);
+        difficulties += U256::from(difficulty);
+    }
+
+    // avg(D) * (N (N + 1) / 2 * T) / sum(i * t_i)
+    let next = difficulties * U256::from(samples + 1) * U256::from(target_time)
+        / (U256::from(2) * weighted_solve_times);
+    Some(saturate(next))
+}
+
+/// ASERT difficulty: the anchor's difficulty, halved for every `half_life` the chain
+/// is behind its ideal schedule and doubled for every `half_life` it is ahead.
+///
+/// `blocks` and `elapsed` are the number of blocks and the time (in milliseconds)
+/// since the anchor block. The exponent is kept in 16.16 fixed point, rounded down,
+/// and its fractional part approximated by the cubic polynomial of aserti3-2d
+/// (within 0.013%).
+pub fn asert(
+    anchor_difficulty: u64,
+    blocks: u64,
+    elapsed: u64,
+    target_time: u64,
+    half_life: u64,
+) -> u64 {
+    const RADIX: i128 = 1 << 16;
+
+    let ahead = i128::from(blocks) * i128::from(target_time) - i128::from(elapsed);
+    let exponent = (ahead * RADIX).div_euclid(i128::from(half_life.max(1)));
+    let shifts = exponent >> 16;
+    let fraction = (exponent & (RADIX - 1)) as u128;
+
+    // 2^(fraction / 2^16) * 2^16, exact at both ends of [0, 1)
+    let factor = (1u128 << 16)
+        + ((195_766_423_245_049 * fraction
+            + 971_821_376 * fraction.pow(2)
+            + 5_127 * fraction.pow(3)
+            + (1 << 47))
+            >> 48);
+
+    let scaled = U256::from(anchor_difficulty.max(1)) * U256::from(factor);
+    let next = match shifts {
+        // Far enough out that the result saturates either way
+        64.. => return u64::MAX,
+        ..=-128 => return 1,
+        0.. => (scaled << shifts as usize) >> 16,
+        _ => scaled >> (16 - shifts) as usize,
+    };
+    saturate(next)
+}
+
+/// `value` as a difficulty: at least 1, at most `u64::MAX`.
+fn saturate(value: U256) -> u64 {
+    if value > U256::from(u64::MAX) {
+        u64::MAX
+    } else {
+        value.low_u64().max(1)
+    }
+}
@@ -0,0 +1,62 @@
+//! Fork choice by cumulative work.
+//!
+//! The node stores a `ChainWork` record for every imported block in auxiliary
+//! storage. The best block is the one whose chain carries the most work, so a short
+//! chain mined at a hard target beats a longer one mined at an easy target. On
+//! equal work the lower block hash wins, which makes every node settle on the same
+//! block whatever order it imported the forks in.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use parity_scale_codec::{Decode, Encode};
+use primitive_types::{H256, U256};
+use scale_info::TypeInfo;
+
+/// Prefix of the auxiliary-storage key holding a block's `ChainWork`.
+pub const AUX_PREFIX: &[u8] = b"pow:chain-work:";
+
+/// Work recorded for one block.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Encode, Decode, TypeInfo)]
+pub struct ChainWork {
+    /// Target the block was mined at
+    pub target: U256,
+    /// Work of the chain from genesis up to and including the block
+    pub total: U256,
+}
+
+impl ChainWork {
+    /// Record for a child of this block mined at `target`.
+    pub fn extend(&self, target: U256) -> Self {
+        Self {
+            target,
+            total: self.total.saturating_add(block_work(target)),
+        }
+    }
+}
+
+/// Expected hashes to mine a block at `target`, `U256::MAX / target`: the
+/// difficulty of `target::Target`, without its `u64` cap. A zero target, which no
+/// hash meets, counts as the most work.
+pub fn block_work(target: U256) -> U256 {
+    U256::MAX / target.max(U256::one())
+}
+
+/// Auxiliary-storage key of the `ChainWork` of block `hash`.
+pub fn aux_key(hash: &H256) -> Vec<u8> {
+    [AUX_PREFIX, hash.as_bytes()].concat()
+}
+
+/// Whether block `candidate` with `candidate_work` should replace `best` as the best
+/// block: more total work wins, then the lower hash.
+pub fn is_better(
+    candidate: &H256,
+    candidate_work: &ChainWork,
+    best: &H256,
+    best_work: &ChainWork,
+) -> bool {
+    match candidate_work.total.cmp(&best_work.total) {
+        Ordering::Equal => candidate < best,
+        ordering => ordering == Ordering::Greater,
+    }
+}
@@ -0,0 +1,89 @@
+//! Digest items shared by the node and the runtime for BLAKE3 proof of work.
+//!
+//! `sc-consensus-pow` places two items in every mined header, both under the PoW
+//! engine ID: a pre-runtime digest fixed before mining starts, and the seal appended
+//! once a nonce is found. The runtime only ever sees the pre-runtime digest (the
+//! seal is stripped before execution), so the miner account is carried in both and
+//! the node rejects blocks where they disagree. The pre-runtime digest is part of the
+//! pre-hash the nonce is searched for, which binds the work to the miner.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod difficulty;
+pub mod fork_choice;
+pub mod target;
+pub mod time;
+
+use parity_scale_codec::{Decode, DecodeAll, Encode};
+use scale_info::TypeInfo;
+
+/// Engine ID of both digest items.
+pub use sp_consensus_pow::POW_ENGINE_ID as ENGINE_ID;
+
+/// Account credited for a block: the Blake3 hash of the miner's Dilithium key.
+pub type AccountId = [u8; 32];
+
+/// Proof-of-work algorithm a block was mined with. Encoded as a single version byte,
+/// so digests from an unknown algorithm fail to decode.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub enum Algorithm {
+    /// BLAKE3 of `pre_hash || nonce_le`, first 4 hash bytes compared little-endian.
+    /// Superseded by `Blake3V2`; nodes no longer mine or accept it.
+    #[codec(index = 1)]
+    Blake3V1,
+    /// BLAKE3 of `pre_hash || nonce_le`, read as a big-endian 256-bit number and
+    /// compared in full against the target. Miners search on its first 4 bytes,
+    /// big-endian, and check each candidate in full.
+    #[codec(index = 2)]
+    Blake3V2,
+}
+
+impl Algorithm {
+    /// The algorithm blocks are mined and checked with.
+    pub const CURRENT: Self = Self::Blake3V2;
+}
+
+/// Pre-runtime digest: who mines the block, with which algorithm.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct PreDigest {
+    pub algorithm: Algorithm,
+    pub miner: AccountId,
+}
+
+/// Seal digest: the solution for the block's pre-hash.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub struct Seal {
+    pub algorithm: Algorithm,
+    pub nonce: u64,
+    pub miner: AccountId,
+}
+
+impl PreDigest {
+    /// Decodes `data`, rejecting trailing bytes.
+    pub fn decode_strict(mut data: &[u8]) -> Option<Self> {
+        Self::decode_all(&mut data).ok()
+    }
+
+    /// Miner account from the first PoW pre-runtime digest among `digests`, which
+    /// must decode strictly.
+    pub fn find_miner<'a>(
+        digests: impl IntoIterator<Item = ([u8; 4], &'a [u8])>,
+    ) -> Option<AccountId> {
+        let (_, data) = digests.into_iter().find(|(id, _)| *id == ENGINE_ID)?;
+        Self::decode_strict(data).map(|digest| digest.miner)
+    }
+}
+
+impl Seal {
+    /// Decodes `data`, rejecting trailing bytes.
+    pub fn decode_strict(mut data: &[u8]) -> Option<Self> {
+        Self::decode_all(&mut data).ok()
+    }
+
+    /// Whether this seal was produced for `pre_digest`.
+    pub fn matches(&self, pre_digest: &PreDigest) -> bool {
+        self.algorithm == pre_digest.algorithm && self.miner == pre_digest.miner
+    }
+}
@@ -0,0 +1,113 @@
+//! 256-bit proof-of-work targets.
+//!
+//! A hash meets a target when, read as a big-endian 256-bit number, it does not
+//! exceed it. Difficulty is the expected number of hashes per solution,
+//! `U256::MAX / target`; for every `u64` difficulty the two convert back and forth
+//! exactly.
+//!
+//! Targets are SCALE-encoded compactly, as the bytes of their big-endian form
+//! without leading zeros (compact length, then the bytes). The harder the target,
+//! the shorter the encoding; at most 33 bytes.
+
+use alloc::vec::Vec;
+use parity_scale_codec::{Decode, Encode, EncodeLike, Error, Input, MaxEncodedLen, Output};
+pub use primitive_types::U256;
+use scale_info::{build::Fields, Path, Type, TypeInfo};
+
+/// A non-zero proof-of-work target.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Target(U256);
+
+impl Target {
+    /// The easiest target, met by every hash: difficulty 1.
+    pub const MAX: Target = Target(U256::MAX);
+
+    /// `None` for zero, which no hash meets.
+    pub fn new(target: U256) -> Option<Self> {
+        (!target.is_zero()).then_some(Self(target))
+    }
+
+    /// Target at which a solution takes `difficulty` hashes on average; `None` for
+    /// a difficulty of zero.
+    pub fn from_difficulty(difficulty: u64) -> Option<Self> {
+        (difficulty != 0).then(|| Self(U256::MAX / U256::from(difficulty)))
+    }
+
+    /// Expected hashes per solution, rounded down and capped at `u64::MAX`.
+    pub fn difficulty(&self) -> u64 {
+        let difficulty = U256::MAX / self.0;
+        if difficulty > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            difficulty.low_u64()
+        }
+    }
+
+    pub fn as_u256(&self) -> U256 {
+        self.0
+    }
+
+    /// Whether `hash` (big-endian, at most 32 bytes) meets the target.
+    pub fn is_met_by(&self, hash: &[u8]) -> bool {
+        hash.len() <= 32 && U256::from_big_endian(hash) <= self.0
+    }
+
+    /// Big-endian bytes without leading zeros.
+    fn significant_bytes(&self) -> Vec<u8> {
+        let mut bytes = [0u8; 32];
+        self.0.to_big_endian(&mut bytes);
+        let leading_zeros = self.0.leading_zeros() as usize / 8;
+        bytes[leading_zeros..].to_vec()
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Self::MAX
+    }
+}
+
+impl Encode for Target {
+    fn size_hint(&self) -> usize {
+        self.significant_bytes().size_hint()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        self.significant_bytes().encode_to(dest)
+    }
+}
+
+impl EncodeLike for Target {}
+
+impl Decode for Target {
+    /// Only the canonical encoding of a non-zero target decodes.
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let bytes = Vec::<u8>::decode(input)?;
+        if bytes.len() > 32 {
+            return Err("Target longer than 256 bits".into());
+        }
+        match bytes.first() {
+            None => Err("Target is zero".into()),
+            Some(0) => Err("Target has leading zero bytes".into()),
+            Some(_) => Ok(Self(U256::from_big_endian(&bytes))),
+        }
+    }
+}
+
+impl MaxEncodedLen for Target {
+    fn max_encoded_len() -> usize {
+        // One length byte covers up to 63 bytes
+        1 + 32
+    }
+}
+
+impl TypeInfo for Target {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder()
+            .path(Path::new("Target", module_path!()))
+            .docs(&["Big-endian bytes of a 256-bit target, without leading zeros"])
+            .composite(Fields::unnamed().field(|f| f.ty::<Vec<u8>>().type_name("Vec<u8>")))
+    }
+}
@@ -0,0 +1,77 @@
+//! Block time rules.
+//!
+//! The difficulty retarget measures elapsed time between blocks, so miners must not
+//! be free to pick timestamps. A block's time (milliseconds since the Unix epoch)
+//! must be later than the median of the last few block times, which keeps the chain's
+//! clock moving forward even when some miners lie, and no more than a drift ahead of
+//! the importing node's clock.
+
+use alloc::vec::Vec;
+
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+/// Identifier for block time checks. Its inherent data is the local time (a `u64`
+/// in milliseconds) of the node importing the block; rejected block times are
+/// reported under it as an [`InherentError`].
+pub const INHERENT_IDENTIFIER: [u8; 8] = *b"powtime0";
+
+/// Why a block time was rejected.
+#[derive(Clone, PartialEq, Eq, Debug, Encode, Decode, TypeInfo)]
+pub enum InherentError {
+    /// Not later than the median time of recent blocks
+    NotAfterMedianTimePast { timestamp: u64, median: u64 },
+    /// More than `max_drift` ahead of the importing node's clock
+    TooFarInFuture {
+        timestamp: u64,
+        local_time: u64,
+        max_drift: u64,
+    },
+    /// The importing node supplied no local time to check against
+    MissingLocalTime,
+}
+
+impl sp_inherents::IsFatalError for InherentError {
+    fn is_fatal_error(&self) -> bool {
+        true
+    }
+}
+
+/// Median of `recent` block times, or `None` before the first block. Even-sized
+/// windows use the upper middle value.
+pub fn median_time_past(recent: &[u64]) -> Option<u64> {
+    let mut sorted: Vec<u64> = recent.to_vec();
+    sorted.sort_unstable();
+    sorted.get(sorted.len() / 2).copied()
+}
+
+/// Earliest time the next block may have.
+pub fn earliest_next(recent: &[u64]) -> u64 {
+    median_time_past(recent).map_or(0, |median| median.saturating_add(1))
+}
+
+/// Checks `timestamp` against the median of `recent` block times.
+pub fn check_median_time_past(timestamp: u64, recent: &[u64]) -> Result<(), InherentError> {
+    match median_time_past(recent) {
+        Some(median) if timestamp <= median => {
+            Err(InherentError::NotAfterMedianTimePast { timestamp, median })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that `timestamp` is at most `max_drift` ahead of `local_time`.
+pub fn check_future_drift(
+    timestamp: u64,
+    local_time: u64,
+    max_drift: u64,
+) -> Result<(), InherentError> {
+    if timestamp > local_time.saturating_add(max_drift) {
+        return Err(InherentError::TooFarInFuture {
+            timestamp,
+            local_time,
+            max_drift,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,253 @@
+//! The retarget rule against a reference model in 128-bit arithmetic.
+
+use pow_primitives::difficulty::{retarget, MAX_ADJUSTMENT_FACTOR};
+use proptest::prelude::*;
+
+/// The rule as specified: exact `difficulty * target / actual`, rounded down, then
+/// clamped to a factor of 4 either way and at least 1.
+fn reference(difficulty: u64, actual: u64, target: u64) -> u64 {
+    let difficulty = u128::from(difficulty.max(1));
+    let lowest = (difficulty / 4).max(1);
+    let highest = (difficulty * 4).min(u128::from(u64::MAX));
+    let scaled = match actual {
+        0 => highest,
+        actual => difficulty * u128::from(target) / u128::from(actual),
+    };
+    scaled.clamp(lowest, highest) as u64
+}
+
+/// Values skewed towards the edges of the range.
+fn edgy() -> impl Strategy<Value = u64> {
+    prop_oneof![
+        Just(0),
+        Just(1),
+        Just(u64::MAX),
+        0..1_000u64,
+        any::<u64>(),
+        any::<u32>().prop_map(u64::from),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn matches_reference_model(difficulty in edgy(), actual in edgy(), target in edgy()) {
+        prop_assert_eq!(retarget(difficulty, actual, target), reference(difficulty, actual, target));
+    }
+
+    #[test]
+    fn adjusts_at_most_fourfold(difficulty in 1..u64::MAX / 4, actual in edgy(), target in edgy()) {
+        let next = retarget(difficulty, actual, target);
+        prop_assert!(next <= difficulty * MAX_ADJUSTMENT_FACTOR);
+        prop_assert!(next >= (difficulty / MAX_ADJUSTMENT_FACTOR).max(1));
+    }
+
+    #[test]
+    fn slower_windows_never_raise_difficulty(
+        difficulty in edgy(),
+        target in edgy(),
+        actual in edgy(),
+        extra in edgy(),
+    ) {
+        let slower = actual.saturating_add(extra);
+        prop_assert!(retarget(difficulty, slower, target) <= retarget(difficulty, actual, target));
+    }
+
+    #[test]
+    fn on_target_window_keeps_difficulty(difficulty in 1..=u64::MAX, target in 1..=u64::MAX) {
+        prop_assert_eq!(retarget(difficulty, target, target), difficulty);
+    }
+}
+
+#[test]
+fn matches_reference_model_exhaustively_on_small_values() {
+    for difficulty in 0..=64 {
+        for actual in 0..=64 {
+            for target in 0..=16 {
+                assert_eq!(
+                    retarget(difficulty, actual, target),
+                    reference(difficulty, actual, target),
+                    "difficulty {difficulty}, actual {actual}, target {target}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn instant_window_raises_by_the_full_factor() {
+    assert_eq!(retarget(1_000, 0, 60_000), 4_000);
+    assert_eq!(retarget(u64::MAX, 0, 60_000), u64::MAX);
+}
+
+#[test]
+fn stalled_window_lowers_by_the_full_factor() {
+    assert_eq!(retarget(1_000, u64::MAX, 60_000), 250);
+    assert_eq!(retarget(3, u64::MAX, 60_000), 1);
+}
+
+#[test]
+fn rounds_down() {
+    // 10 * 2 / 3 = 6.67
+    assert_eq!(retarget(10, 3, 2), 6);
+}
+
+mod lwma {
+    use super::*;
+    use pow_primitives::difficulty::lwma;
+
+    const TARGET: u64 = 12_000;
+
+    /// Blocks `solve_times` apart, all at `difficulty`, starting at time 0.
+    fn chain(solve_times: &[u64], difficulty: u64) -> Vec<(u64, u64)> {
+        let mut time = 0;
+        let mut blocks = vec![(time, difficulty)];
+        for solve_time in solve_times {
+            time += solve_time;
+            blocks.push((time, difficulty));
+        }
+        blocks
+    }
+
+    /// LWMA-1 as usually written: weighted solve time against its value on target.
+    fn reference(blocks: &[(u64, u64)], target: u64) -> u64 {
+        let n = blocks.len() as u128 - 1;
+        let (mut weighted, mut sum) = (0u128, 0u128);
+        for i in 1..blocks.len() {
+            let solve_time = blocks[i]
+                .0
+                .saturating_sub(blocks[i - 1].0)
+                .clamp(1, (6 * target).max(1));
+            weighted += i as u128 * u128::from(solve_time);
+            sum += u128::from(blocks[i].1);
+        }
+        let average = sum * (n * (n + 1) / 2) * u128::from(target);
+        (average / (n * weighted)).clamp(1, u128::from(u64::MAX)) as u64
+    }
+
+    #[test]
+    fn needs_two_blocks() {
+        assert_eq!(lwma(&[], TARGET), None);
+        assert_eq!(lwma(&[(0, 5)], TARGET), None);
+        assert_eq!(lwma(&chain(&[TARGET], 5), TARGET), Some(5));
+    }
+
+    #[test]
+    fn steady_chain_keeps_difficulty() {
+        assert_eq!(
+            lwma(&chain(&[TARGET; 60], 1_000_000), TARGET),
+            Some(1_000_000)
+        );
+    }
+
+    #[test]
+    fn recent_blocks_weigh_more() {
+        let mut early_burst = vec![TARGET / 2; 30];
+        early_burst.extend([TARGET; 30]);
+        let mut late_burst = vec![TARGET; 30];
+        late_burst.extend([TARGET / 2; 30]);
+        let early = lwma(&chain(&early_burst, 1_000_000), TARGET).unwrap();
+        let late = lwma(&chain(&late_burst, 1_000_000), TARGET).unwrap();
+        assert!(1_000_000 < early && early < late);
+    }
+
+    #[test]
+    fn stalled_block_counts_as_six_targets() {
+        let mut solve_times = vec![TARGET; 59];
+        solve_times.push(1_000 * TARGET);
+        let capped = {
+            let mut solve_times = solve_times.clone();
+            solve_times[59] = 6 * TARGET;
+            solve_times
+        };
+        assert_eq!(
+            lwma(&chain(&solve_times, 1_000_000), TARGET),
+            lwma(&chain(&capped, 1_000_000), TARGET)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference_model(
+            solve_times in prop::collection::vec(0..100_000u64, 1..90),
+            difficulties in prop::collection::vec(1..u64::MAX / 128, 90),
+            target in 1..60_000u64,
+        ) {
+            let mut time = 0;
+            let mut blocks = vec![(time, difficulties[0])];
+            for (solve_time, difficulty) in solve_times.iter().zip(&difficulties[1..]) {
+                time += solve_time;
+                blocks.push((time, *difficulty));
+            }
+            prop_assert_eq!(lwma(&blocks, target), Some(reference(&blocks, target)));
+        }
+    }
+}
+
+mod asert {
+    use super::*;
+    use pow_primitives::difficulty::asert;
+
+    const TARGET: u64 = 12_000;
+    const HALF_LIFE: u64 = 3_600_000;
+
+    #[test]
+    fn on_schedule_keeps_anchor_difficulty() {
+        for blocks in [0, 1, 100, 1_000_000] {
+            assert_eq!(
+                asert(1_000_000, blocks, blocks * TARGET, TARGET, HALF_LIFE),
+                1_000_000
+            );
+        }
+    }
+
+    #[test]
+    fn halves_and_doubles_per_half_life() {
+        let behind = 100 * TARGET + HALF_LIFE;
+        assert_eq!(asert(1_000_000, 100, behind, TARGET, HALF_LIFE), 500_000);
+        let ahead = 400 * TARGET - HALF_LIFE;
+        assert_eq!(asert(1_000_000, 400, ahead, TARGET, HALF_LIFE), 2_000_000);
+        assert_eq!(
+            asert(1_000_000, 0, 3 * HALF_LIFE, TARGET, HALF_LIFE),
+            125_000
+        );
+    }
+
+    #[test]
+    fn saturates_far_from_schedule() {
+        assert_eq!(asert(1_000_000, 0, u64::MAX, TARGET, HALF_LIFE), 1);
+        assert_eq!(
+            asert(1_000_000, u64::from(u32::MAX), 0, TARGET, HALF_LIFE),
+            u64::MAX
+        );
+        assert_eq!(asert(u64::MAX, 1, 0, TARGET, 1), u64::MAX);
+    }
+
+    proptest! {
+        #[test]
+        fn tracks_the_exponential_within_rounding(
+            anchor in 1_000..1_000_000_000_000u64,
+            blocks in 0..10_000u64,
+            elapsed in 0..200_000_000u64,
+        ) {
+            let next = asert(anchor, blocks, elapsed, TARGET, HALF_LIFE);
+            let exponent = (blocks as f64 * TARGET as f64 - elapsed as f64) / HALF_LIFE as f64;
+            let ideal = anchor as f64 * exponent.exp2();
+            prop_assume!(ideal >= 1.0 && ideal < u64::MAX as f64 / 2.0);
+            // The polynomial is within 0.013% of 2^x, plus rounding down
+            prop_assert!((next as f64 - ideal).abs() <= ideal * 1.3e-4 + 1.0, "next {next}, ideal {ideal}");
+        }
+
+        #[test]
+        fn slower_chains_get_lower_difficulty(
+            anchor in 1..u64::MAX,
+            blocks in 0..1_000_000u64,
+            elapsed in 0..u64::MAX / 2,
+            extra in 0..u64::MAX / 2,
+        ) {
+            prop_assert!(
+                asert(anchor, blocks, elapsed + extra, TARGET, HALF_LIFE)
+                    <= asert(anchor, blocks, elapsed, TARGET, HALF_LIFE)
+            );
+        }
+    }
+}
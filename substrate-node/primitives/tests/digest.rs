@@ -0,0 +1,77 @@
+use parity_scale_codec::Encode;
+use pow_primitives::{Algorithm, PreDigest, Seal, ENGINE_ID};
+
+const MINER: [u8; 32] = [7; 32];
+
+fn seal() -> Seal {
+    Seal {
+        algorithm: Algorithm::Blake3V2,
+        nonce: 0x0102_0304_0506_0708,
+        miner: MINER,
+    }
+}
+
+#[test]
+fn seal_layout_is_version_nonce_miner() {
+    let encoded = seal().encode();
+    assert_eq!(encoded.len(), 1 + 8 + 32);
+    assert_eq!(encoded[0], 2);
+    assert_eq!(&encoded[1..9], &0x0102_0304_0506_0708u64.to_le_bytes());
+    assert_eq!(&encoded[9..], &MINER);
+    assert_eq!(Seal::decode_strict(&encoded), Some(seal()));
+}
+
+#[test]
+fn malformed_seals_are_rejected() {
+    let encoded = seal().encode();
+    // Truncated, trailing bytes, unknown algorithm versions
+    assert_eq!(Seal::decode_strict(&encoded[..encoded.len() - 1]), None);
+    assert_eq!(Seal::decode_strict(&[&encoded[..], &[0]].concat()), None);
+    for version in [0, 3, 255] {
+        let mut unknown = encoded.clone();
+        unknown[0] = version;
+        assert_eq!(Seal::decode_strict(&unknown), None);
+    }
+    // A bare nonce, the seal format before digests were versioned
+    assert_eq!(Seal::decode_strict(&7u64.encode()), None);
+}
+
+#[test]
+fn retired_algorithm_decodes_but_is_not_current() {
+    let retired = Seal {
+        algorithm: Algorithm::Blake3V1,
+        ..seal()
+    };
+    assert_eq!(retired.encode()[0], 1);
+    assert_eq!(Seal::decode_strict(&retired.encode()), Some(retired));
+    assert_ne!(Algorithm::CURRENT, Algorithm::Blake3V1);
+}
+
+#[test]
+fn seal_must_match_pre_digest() {
+    let pre_digest = PreDigest {
+        algorithm: Algorithm::Blake3V2,
+        miner: MINER,
+    };
+    assert!(seal().matches(&pre_digest));
+    let other = PreDigest {
+        miner: [8; 32],
+        ..pre_digest
+    };
+    assert!(!seal().matches(&other));
+}
+
+#[test]
+fn miner_is_found_in_pow_pre_runtime_digest() {
+    let pre_digest = PreDigest {
+        algorithm: Algorithm::Blake3V2,
+        miner: MINER,
+    }
+    .encode();
+    let digests = [(*b"aura", &[0u8; 8][..]), (ENGINE_ID, &pre_digest[..])];
+    assert_eq!(PreDigest::find_miner(digests), Some(MINER));
+
+    let malformed = [&pre_digest[..], &[0]].concat();
+    assert_eq!(PreDigest::find_miner([(ENGINE_ID, &malformed[..])]), None);
+    assert_eq!(PreDigest::find_miner([(*b"aura", &pre_digest[..])]), None);
+}
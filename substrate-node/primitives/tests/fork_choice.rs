@@ -0,0 +1,176 @@
+//! Imports forked block trees in different orders and checks that the best block
+//! always ends up at the chain with the most work, ties going to the lower hash.
+
+use std::collections::HashMap;
+
+use pow_primitives::{
+    fork_choice::{block_work, is_better, ChainWork},
+    target::Target,
+};
+use primitive_types::{H256, U256};
+
+const GENESIS: H256 = H256::zero();
+
+/// Node-side view of block import: auxiliary records and the best block.
+struct Importer {
+    aux: HashMap<H256, ChainWork>,
+    best: H256,
+}
+
+impl Importer {
+    fn new() -> Self {
+        Self {
+            aux: HashMap::from([(GENESIS, ChainWork::default())]),
+            best: GENESIS,
+        }
+    }
+
+    fn import(&mut self, block: &Block) {
+        let target = Target::from_difficulty(block.difficulty).unwrap();
+        let work = self.aux[&block.parent].extend(target.as_u256());
+        if is_better(&block.hash, &work, &self.best, &self.aux[&self.best]) {
+            self.best = block.hash;
+        }
+        self.aux.insert(block.hash, work);
+    }
+}
+
+#[derive(Clone)]
+struct Block {
+    hash: H256,
+    parent: H256,
+    difficulty: u64,
+}
+
+/// Appends a chain of blocks at `difficulties` on top of `parent`.
+fn chain(blocks: &mut Vec<Block>, parent: H256, difficulties: &[u64]) -> H256 {
+    let mut parent = parent;
+    for &difficulty in difficulties {
+        let hash = H256::from_low_u64_be(blocks.len() as u64 * 7919 % 10007 + 1);
+        blocks.push(Block {
+            hash,
+            parent,
+            difficulty,
+        });
+        parent = hash;
+    }
+    parent
+}
+
+fn best_after_importing(blocks: &[Block]) -> H256 {
+    let mut importer = Importer::new();
+    blocks.iter().for_each(|block| importer.import(block));
+    importer.best
+}
+
+/// Small deterministic generator, so failures reproduce.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+/// Reorders `blocks` randomly while keeping every parent before its children.
+fn shuffle_topological(blocks: &[Block], rng: &mut Lcg) -> Vec<Block> {
+    let mut pending = blocks.to_vec();
+    let mut imported = vec![GENESIS];
+    let mut order = Vec::with_capacity(blocks.len());
+    while !pending.is_empty() {
+        let ready: Vec<_> = (0..pending.len())
+            .filter(|&i| imported.contains(&pending[i].parent))
+            .collect();
+        let block = pending.remove(ready[rng.next(ready.len() as u64) as usize]);
+        imported.push(block.hash);
+        order.push(block);
+    }
+    order
+}
+
+#[test]
+fn short_hard_chain_beats_long_easy_chain() {
+    let mut blocks = Vec::new();
+    let easy = chain(&mut blocks, GENESIS, &[10; 5]);
+    let hard = chain(&mut blocks, GENESIS, &[30, 30]);
+    assert_eq!(best_after_importing(&blocks), hard);
+
+    let mut rng = Lcg(7);
+    for _ in 0..10 {
+        assert_eq!(
+            best_after_importing(&shuffle_topological(&blocks, &mut rng)),
+            hard
+        );
+    }
+
+    // Once the easy chain carries more work it takes over
+    let easy = chain(&mut blocks, easy, &[11]);
+    assert_eq!(best_after_importing(&blocks), easy);
+}
+
+#[test]
+fn equal_work_goes_to_the_lower_hash_in_any_order() {
+    let mut blocks = Vec::new();
+    let a = chain(&mut blocks, GENESIS, &[20, 20]);
+    let b = chain(&mut blocks, GENESIS, &[10, 10, 20]);
+    let expected = a.min(b);
+
+    assert_eq!(best_after_importing(&blocks), expected);
+    let mut rng = Lcg(1);
+    for _ in 0..20 {
+        assert_eq!(
+            best_after_importing(&shuffle_topological(&blocks, &mut rng)),
+            expected
+        );
+    }
+}
+
+#[test]
+fn random_fork_trees_converge_on_the_most_work() {
+    let mut rng = Lcg(42);
+    for _ in 0..20 {
+        // Random tree: every block extends a random earlier block; small
+        // difficulties make equal-work ties common
+        let mut blocks: Vec<Block> = Vec::new();
+        for _ in 0..60 {
+            let parent = match rng.next(blocks.len() as u64 + 1) {
+                0 => GENESIS,
+                i => blocks[i as usize - 1].hash,
+            };
+            chain(&mut blocks, parent, &[1 + rng.next(4)]);
+        }
+
+        // Reference: total work of every block, then the maximum with lowest hash
+        let mut totals = HashMap::from([(GENESIS, 0u64)]);
+        for block in &blocks {
+            totals.insert(block.hash, totals[&block.parent] + block.difficulty);
+        }
+        let expected = blocks
+            .iter()
+            .map(|block| (totals[&block.hash], std::cmp::Reverse(block.hash)))
+            .max()
+            .map(|(_, std::cmp::Reverse(hash))| hash)
+            .unwrap();
+
+        for _ in 0..5 {
+            let order = shuffle_topological(&blocks, &mut rng);
+            assert_eq!(best_after_importing(&order), expected);
+        }
+    }
+}
+
+#[test]
+fn block_work_is_the_difficulty_of_the_target() {
+    assert_eq!(block_work(Target::MAX.as_u256()), U256::one());
+    for difficulty in [2, 3, 1_000_000, u64::MAX] {
+        let target = Target::from_difficulty(difficulty).unwrap();
+        assert_eq!(block_work(target.as_u256()), U256::from(difficulty));
+    }
+    // Targets below `U256::MAX / u64::MAX` are worth more than any `u64`
+    assert_eq!(block_work(U256::one()), U256::MAX);
+    assert_eq!(ChainWork::default().extend(U256::zero()).total, U256::MAX);
+}
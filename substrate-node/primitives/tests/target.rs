@@ -0,0 +1,93 @@
+//! Targets: conversion from and to difficulty, the hash comparison and the compact
+//! encoding.
+
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use pow_primitives::target::Target;
+use primitive_types::U256;
+use proptest::prelude::*;
+
+fn be_bytes(value: U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+#[test]
+fn zero_is_not_a_target() {
+    assert_eq!(Target::new(U256::zero()), None);
+    assert_eq!(Target::from_difficulty(0), None);
+}
+
+#[test]
+fn difficulty_one_accepts_every_hash() {
+    let target = Target::from_difficulty(1).unwrap();
+    assert_eq!(target, Target::MAX);
+    assert_eq!(target, Target::default());
+    assert!(target.is_met_by(&[0xff; 32]));
+}
+
+#[test]
+fn hash_is_compared_in_full() {
+    let target = Target::new(U256::from(0x1234) << 200).unwrap();
+    let at = be_bytes(target.as_u256());
+    let above = be_bytes(target.as_u256() + 1);
+    let below = be_bytes(target.as_u256() - 1);
+    assert!(target.is_met_by(&at));
+    assert!(target.is_met_by(&below));
+    // Differs from the target only in the last byte
+    assert!(!target.is_met_by(&above));
+    assert!(!target.is_met_by(&[0u8; 33]));
+}
+
+#[test]
+fn difficulty_beyond_u64_saturates() {
+    let target = Target::new(U256::one()).unwrap();
+    assert_eq!(target.difficulty(), u64::MAX);
+    let max = Target::from_difficulty(u64::MAX).unwrap();
+    assert_eq!(max.difficulty(), u64::MAX);
+    assert!(target < max);
+}
+
+#[test]
+fn encoding_drops_leading_zero_bytes() {
+    assert_eq!(Target::MAX.encode().len(), Target::max_encoded_len());
+    let hard = Target::new(U256::from(0xabcd) << 64).unwrap();
+    assert_eq!(
+        hard.encode(),
+        [&[10 << 2][..], &[0xab, 0xcd], &[0; 8]].concat()
+    );
+}
+
+#[test]
+fn non_canonical_encodings_are_rejected() {
+    // Zero
+    assert!(Target::decode(&mut &[0u8][..]).is_err());
+    // Leading zero byte
+    assert!(Target::decode(&mut &[2 << 2, 0, 1][..]).is_err());
+    // Longer than 256 bits
+    let long = [&[33 << 2][..], &[1; 33]].concat();
+    assert!(Target::decode(&mut &long[..]).is_err());
+}
+
+proptest! {
+    #[test]
+    fn difficulty_round_trips(difficulty in 1u64..) {
+        let target = Target::from_difficulty(difficulty).unwrap();
+        prop_assert_eq!(target.difficulty(), difficulty);
+    }
+
+    #[test]
+    fn higher_difficulty_means_lower_target(a in 1u64.., b in 1u64..) {
+        prop_assume!(a <= b);
+        prop_assert!(Target::from_difficulty(a).unwrap() >= Target::from_difficulty(b).unwrap());
+    }
+
+    #[test]
+    fn encoding_round_trips(bytes in any::<[u8; 32]>()) {
+        prop_assume!(bytes != [0; 32]);
+        let target = Target::new(U256::from_big_endian(&bytes)).unwrap();
+        let encoded = target.encode();
+        prop_assert!(encoded.len() <= Target::max_encoded_len());
+        prop_assert_eq!(Target::decode(&mut &encoded[..]).unwrap(), target);
+    }
+}
@@ -0,0 +1,129 @@
+//! Block time rules against honest and adversarial timestamp series.
+
+use pow_primitives::time::{
+    check_future_drift, check_median_time_past, earliest_next, median_time_past, InherentError,
+};
+
+const SPAN: usize = 11;
+const BLOCK_TIME: u64 = 60_000;
+
+/// Chain of accepted block times, keeping the last `SPAN` for the median.
+struct Chain {
+    times: Vec<u64>,
+}
+
+impl Chain {
+    fn new() -> Self {
+        Self { times: Vec::new() }
+    }
+
+    fn recent(&self) -> &[u64] {
+        &self.times[self.times.len().saturating_sub(SPAN)..]
+    }
+
+    /// Appends `timestamp` if the median rule accepts it.
+    fn push(&mut self, timestamp: u64) -> Result<(), InherentError> {
+        check_median_time_past(timestamp, self.recent())?;
+        self.times.push(timestamp);
+        Ok(())
+    }
+}
+
+#[test]
+fn median_of_window() {
+    assert_eq!(median_time_past(&[]), None);
+    assert_eq!(median_time_past(&[5]), Some(5));
+    assert_eq!(median_time_past(&[9, 1, 5]), Some(5));
+    assert_eq!(median_time_past(&[4, 1, 3, 2]), Some(3));
+    assert_eq!(earliest_next(&[]), 0);
+    assert_eq!(earliest_next(&[9, 1, 5]), 6);
+}
+
+#[test]
+fn honest_series_is_accepted() {
+    let mut chain = Chain::new();
+    for height in 1..100 {
+        chain.push(height * BLOCK_TIME).unwrap();
+    }
+}
+
+#[test]
+fn timestamp_at_or_before_median_is_rejected() {
+    let mut chain = Chain::new();
+    for height in 1..=SPAN as u64 {
+        chain.push(height * BLOCK_TIME).unwrap();
+    }
+    let median = 6 * BLOCK_TIME;
+    assert_eq!(
+        chain.push(median),
+        Err(InherentError::NotAfterMedianTimePast {
+            timestamp: median,
+            median
+        })
+    );
+    // Earlier than the previous block but after the median is fine
+    chain.push(median + 1).unwrap();
+}
+
+#[test]
+fn minority_of_lying_miners_cannot_drag_time_back() {
+    // Every third block is mined by an attacker who stamps the earliest time allowed;
+    // the others are honest
+    let mut chain = Chain::new();
+    let mut honest_clock = 0;
+    for height in 1..300u64 {
+        honest_clock += BLOCK_TIME;
+        let timestamp = if height % 3 == 0 {
+            earliest_next(chain.recent())
+        } else {
+            honest_clock
+        };
+        chain.push(timestamp).unwrap();
+    }
+    // The median keeps pace with real time, lagging by less than one window
+    let median = median_time_past(chain.recent()).unwrap();
+    assert!(
+        honest_clock - median < SPAN as u64 * BLOCK_TIME,
+        "median lags by {}",
+        honest_clock - median
+    );
+}
+
+#[test]
+fn time_warp_cannot_reverse_the_clock() {
+    // An attacker mining every block tries to make the chain's clock run backwards so
+    // that retargeting sees little elapsed time
+    let mut chain = Chain::new();
+    for height in 1..=SPAN as u64 {
+        chain.push(height * BLOCK_TIME).unwrap();
+    }
+    for _ in 0..500 {
+        let median = median_time_past(chain.recent()).unwrap();
+        assert!(chain.push(median).is_err());
+        assert!(chain.push(median / 2).is_err());
+        chain.push(earliest_next(chain.recent())).unwrap();
+    }
+    // Median time past never decreases along the chain
+    let medians: Vec<_> = (1..=chain.times.len())
+        .map(|end| median_time_past(&chain.times[end.saturating_sub(SPAN)..end]).unwrap())
+        .collect();
+    assert!(medians.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+#[test]
+fn future_drift_is_bounded_by_local_clock() {
+    let local = 1_700_000_000_000;
+    let drift = 5 * 60_000;
+    assert_eq!(check_future_drift(local + drift, local, drift), Ok(()));
+    assert_eq!(check_future_drift(local - 1, local, drift), Ok(()));
+    assert_eq!(
+        check_future_drift(local + drift + 1, local, drift),
+        Err(InherentError::TooFarInFuture {
+            timestamp: local + drift + 1,
+            local_time: local,
+            max_drift: drift
+        })
+    );
+    // A miner jumping far ahead to crash difficulty is rejected outright
+    assert!(check_future_drift(u64::MAX, local, drift).is_err());
+}
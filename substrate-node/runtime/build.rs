@@ -0,0 +1,7 @@
+fn main() {
+    // Compile the runtime to WASM and expose it as `WASM_BINARY`
+    #[cfg(feature = "std")]
+    {
+        substrate_wasm_builder::WasmBuilder::build_using_defaults();
+    }
+}
@@ -1,96 +1,213 @@
 #![cfg_attr(not(feature = "std"), no_std)]
-#![feature(min_specialization)]
 
-use sp_std::prelude::*;
-use frame_support::{construct_runtime, parameter_types};
-use frame_system::EnsureRoot;
-use sp_runtime::{traits::{IdentityLookup, Hash as HashT}, generic::Header as GenericHeader};
+// Make the WASM binary available (built by `build.rs`)
+#[cfg(feature = "std")]
+include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
+
 use blake3::{hash, Hash as Blake3Hash};
-use parity_scale_codec::{Encode, Decode};
-use pqcrypto_dilithium::{PublicKey as DilithiumPublicKey, Signature as DilithiumSignature};
-use sp_runtime::{traits::{Verify, IdentifyAccount}};
-// remove Blake2-based address derivation; we'll use Blake3 via the runtime hasher
-// use sp_core::blake2_256;
-// Include FRAME's balances pallet for account balances and transfers
-use pallet_balances;
-// Use imports for PoW implementation
-use pow_kernel;
-use sp_core::H256;
+use coin_tokenomics::REWARD_SCHEDULE;
+use frame_support::{
+    construct_runtime,
+    genesis_builder_helper::{build_config, create_default_config},
+    parameter_types,
+    traits::{ConstU128, ConstU32, Everything, FindAuthor, Randomness},
+};
+use ml_dsa::{EncodedSignature, EncodedVerifyingKey, MlDsa65, VerifyingKey};
+use pallet_basic_pallet::difficulty::Periodic;
+use parity_scale_codec::{Decode, Encode};
 use primitive_types::U256;
+use scale_info::TypeInfo;
+use sp_api::impl_runtime_apis;
+use sp_core::{crypto::KeyTypeId, OpaqueMetadata, H256};
+use sp_runtime::{
+    create_runtime_str, generic,
+    traits::{Block as BlockT, Hash as HashT, IdentityLookup},
+    transaction_validity::{TransactionSource, TransactionValidity},
+    ApplyExtrinsicResult, ConsensusEngineId, StateVersion,
+};
+use sp_runtime::{
+    traits::{IdentifyAccount, Lazy, Verify},
+    RuntimeDebug,
+};
+use sp_std::prelude::*;
+use sp_trie::TrieConfiguration;
+#[cfg(feature = "std")]
+use sp_version::NativeVersion;
+use sp_version::RuntimeVersion;
 
-/// Post-quantum signature enum
-#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
-pub enum PQSignature {
-    Dilithium(DilithiumPublicKey, DilithiumSignature),
-}
+/// Length of an encoded ML-DSA-65 (FIPS 204) public key
+pub const PUBLIC_KEY_LEN: usize = 1952;
 
-impl Verify for PQSignature {
-    type Signer = <PQSignature as IdentifyAccount>::AccountId;
+/// Length of an encoded ML-DSA-65 signature
+pub const SIGNATURE_LEN: usize = 3309;
 
-    fn verify<L: sp_runtime::traits::VerifySigner<L>>(&self, msg: &[u8]) -> bool {
-        match self {
-            PQSignature::Dilithium(pk, sig) => pqcrypto_dilithium::verify(sig, msg, pk).is_ok(),
-        }
+/// ML-DSA-65 public key, in the FIPS 204 encoding the wallet produces
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PQPublic(pub [u8; PUBLIC_KEY_LEN]);
+
+impl IdentifyAccount for PQPublic {
+    type AccountId = AccountId;
+
+    fn into_account(self) -> AccountId {
+        account_id(&self.0)
     }
 }
 
-impl IdentifyAccount for PQSignature {
-    type AccountId = [u8; 32];
+/// Post-quantum transaction signature. Accounts are only a hash of the public key,
+/// so the key travels with the signature.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum PQSignature {
+    /// ML-DSA-65 signature over the signed payload, with an empty context
+    MlDsa65 {
+        public: PQPublic,
+        signature: [u8; SIGNATURE_LEN],
+    },
+}
 
-    fn into_account(self) -> Self::AccountId {
-        // Derive account ID by hashing the public key with Blake3
+impl Verify for PQSignature {
+    type Signer = PQPublic;
+
+    fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId) -> bool {
         match self {
-            PQSignature::Dilithium(pk, _sig) => {
-                let h = Blake3Hasher::hash(pk.as_bytes());
-                // H256 -> [u8;32]
-                *h.as_ref()
+            PQSignature::MlDsa65 { public, signature } => {
+                if account_id(&public.0) != *signer {
+                    return false;
+                }
+                let (Ok(public), Ok(signature)) = (
+                    EncodedVerifyingKey::<MlDsa65>::try_from(&public.0[..]),
+                    EncodedSignature::<MlDsa65>::try_from(&signature[..]),
+                ) else {
+                    return false;
+                };
+                let Some(signature) = ml_dsa::Signature::<MlDsa65>::decode(&signature) else {
+                    return false;
+                };
+                VerifyingKey::<MlDsa65>::decode(&public).verify_with_context(
+                    msg.get(),
+                    &[],
+                    &signature,
+                )
             }
         }
     }
 }
 
+/// Account of an ML-DSA public key: the Blake3 hash of its encoding
+pub fn account_id(public_key: &[u8]) -> AccountId {
+    AccountId::new(*hash(public_key).as_bytes())
+}
+
 /// Custom Blake3 hasher for the runtime
+// Serde is required in `no_std` too, where `sp-runtime` enables it for genesis configs
+#[derive(PartialEq, Eq, Clone, Debug, TypeInfo, serde::Serialize, serde::Deserialize)]
 pub struct Blake3Hasher;
-impl HashT<sp_core::H256> for Blake3Hasher {
-    fn hash(input: &[u8]) -> sp_core::H256 {
+
+impl sp_core::Hasher for Blake3Hasher {
+    type Out = H256;
+    type StdHasher = hash256_std_hasher::Hash256StdHasher;
+    const LENGTH: usize = 32;
+
+    fn hash(input: &[u8]) -> H256 {
         let h: Blake3Hash = hash(input);
         let mut out = [0u8; 32];
         out.copy_from_slice(h.as_bytes());
-        sp_core::H256::from(out)
+        H256::from(out)
+    }
+}
+
+impl HashT for Blake3Hasher {
+    type Output = H256;
+
+    // Tries are built in the runtime: the host trie functions are Blake2-only
+    fn ordered_trie_root(input: Vec<Vec<u8>>, version: StateVersion) -> H256 {
+        match version {
+            StateVersion::V0 => sp_trie::LayoutV0::<Blake3Hasher>::ordered_trie_root(input),
+            StateVersion::V1 => sp_trie::LayoutV1::<Blake3Hasher>::ordered_trie_root(input),
+        }
+    }
+
+    fn trie_root(input: Vec<(Vec<u8>, Vec<u8>)>, version: StateVersion) -> H256 {
+        match version {
+            StateVersion::V0 => sp_trie::LayoutV0::<Blake3Hasher>::trie_root(input),
+            StateVersion::V1 => sp_trie::LayoutV1::<Blake3Hasher>::trie_root(input),
+        }
+    }
+}
+
+/// Block number type
+pub type BlockNumber = u32;
+
+/// Account nonce type
+pub type Nonce = u32;
+
+/// Balance of an account, in the smallest unit
+pub type Balance = u128;
+
+/// One coin: 8 decimals, as in the TypeScript token model
+pub const UNIT: Balance = coin_tokenomics::UNIT;
+
+/// Accounts whose total balance falls below this are removed, and the rest is burnt
+pub const EXISTENTIAL_DEPOSIT: Balance = UNIT / 1_000;
+
+/// Account ID: the Blake3 hash of the account's ML-DSA public key
+pub type AccountId = sp_runtime::AccountId32;
+
+/// Block header type, hashed with Blake3
+pub type Header = generic::Header<BlockNumber, Blake3Hasher>;
+
+/// Runtime version, checked by the client before executing blocks
+#[sp_version::runtime_version]
+pub const VERSION: RuntimeVersion = RuntimeVersion {
+    spec_name: create_runtime_str!("coin"),
+    impl_name: create_runtime_str!("coin"),
+    authoring_version: 1,
+    spec_version: 106,
+    impl_version: 1,
+    apis: RUNTIME_API_VERSIONS,
+    transaction_version: 1,
+    state_version: 1,
+};
+
+/// The version information used to identify this runtime when compiled natively
+#[cfg(feature = "std")]
+pub fn native_version() -> NativeVersion {
+    NativeVersion {
+        runtime_version: VERSION,
+        can_author_with: Default::default(),
     }
 }
 
 /// Runtime configuration
 impl frame_system::Config for Runtime {
-    type BaseCallFilter = frame_support::traits::Everything;
+    type BaseCallFilter = Everything;
     type BlockWeights = ();
     type BlockLength = ();
     type DbWeight = ();
     type RuntimeOrigin = RuntimeOrigin;
     type RuntimeCall = RuntimeCall;
-    type Index = u32;
-    type BlockNumber = u32;
-    type Hash = sp_core::H256;
+    type Nonce = Nonce;
+    type Hash = H256;
     type Hashing = Blake3Hasher;
-    type AccountId = [u8; 32];
+    type AccountId = AccountId;
     type Lookup = IdentityLookup<Self::AccountId>;
-    type Header = GenericHeader<Self::BlockNumber, Blake3Hasher>;
+    type Block = Block;
     type RuntimeEvent = RuntimeEvent;
     type BlockHashCount = BlockHashCount;
-    type Version = (); 
+    type Version = Version;
     type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<Balance>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
-    type SS58Prefix = (); 
+    type SS58Prefix = ();
     type OnSetCode = ();
-    type MaxConsumers = frame_support::traits::ConstU32<16>;
+    type MaxConsumers = ConstU32<16>;
 }
 
 // Transaction signature and payload configuration
-use frame_system::{CheckSpecVersion, CheckTxVersion, CheckGenesis, CheckEra, CheckNonce, CheckWeight};
-use sp_runtime::generic::SignedPayload;
+use frame_system::{
+    CheckEra, CheckGenesis, CheckNonce, CheckSpecVersion, CheckTxVersion, CheckWeight,
+};
 
 /// Alias to our post-quantum signature type
 pub type Signature = PQSignature;
@@ -106,35 +223,289 @@ pub type SignedExtra = (
 );
 
 /// Unchecked extrinsic type using our PQSignature and full signed extensions
-pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<
-    AccountId,
-    RuntimeCall,
-    Signature,
-    SignedExtra,
->;
+pub type UncheckedExtrinsic =
+    generic::UncheckedExtrinsic<AccountId, RuntimeCall, Signature, SignedExtra>;
 
 /// The block type as expected by the runtime
-pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 
 /// Signed payload type for constructing transactions
-pub type SignedPayload = SignedPayload<RuntimeCall, SignedExtra>;
+pub type SignedPayload = generic::SignedPayload<RuntimeCall, SignedExtra>;
+
+/// Executive: dispatches incoming extrinsics to the respective pallets
+pub type Executive = frame_executive::Executive<
+    Runtime,
+    Block,
+    frame_system::ChainContext<Runtime>,
+    Runtime,
+    AllPalletsWithSystem,
+    Migrations,
+>;
+
+/// Migrations run on every runtime upgrade; each one checks whether it has work to do
+type Migrations = (
+    pallet_basic_pallet::migrations::DifficultyToTarget<Runtime>,
+    pallet_basic_pallet::migrations::LockRewardsPerBlock<Runtime>,
+    pallet_basic_pallet::migrations::StartEmissionLedger<Runtime>,
+    pallet_basic_pallet::migrations::SwitchDifficultyAlgorithm<Runtime>,
+);
+
+/// Block author: the miner named in the PoW pre-runtime digest. Blocks whose digest
+/// does not decode strictly have no author.
+pub struct SealAuthor;
+
+impl FindAuthor<AccountId> for SealAuthor {
+    fn find_author<'a, I>(digests: I) -> Option<AccountId>
+    where
+        I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+    {
+        pow_primitives::PreDigest::find_miner(digests).map(AccountId::new)
+    }
+}
+
+/// Randomness from the parent block hash. Miners can bias it by withholding blocks,
+/// so it must not decide anything worth more than a block reward.
+pub struct ParentHashRandomness;
+
+impl Randomness<H256, BlockNumber> for ParentHashRandomness {
+    fn random(subject: &[u8]) -> (H256, BlockNumber) {
+        let seed = Blake3Hasher::hash_of(&(System::parent_hash(), subject));
+        (seed, System::block_number().saturating_sub(1))
+    }
+}
+
+impl pallet_balances::Config for Runtime {
+    type Balance = Balance;
+    type RuntimeEvent = RuntimeEvent;
+    // Dust of reaped accounts is burnt, reducing total issuance; the mining pallet
+    // records it in its emission ledger
+    type DustRemoval = pallet_basic_pallet::rewards::BurnToLedger<Runtime>;
+    type ExistentialDeposit = ConstU128<EXISTENTIAL_DEPOSIT>;
+    type AccountStore = System;
+    type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
+    type MaxLocks = ConstU32<50>;
+    type MaxReserves = ConstU32<50>;
+    type ReserveIdentifier = [u8; 8];
+    type FreezeIdentifier = [u8; 8];
+    type MaxFreezes = ConstU32<8>;
+    // No pallet places holds yet
+    type RuntimeHoldReason = RuntimeHoldReason;
+    type RuntimeFreezeReason = RuntimeFreezeReason;
+    type MaxHolds = ConstU32<0>;
+}
+
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
 
 /// Configuration for our basic pallet
-impl pallet_basic_pallet::Config for Runtime {}
+impl pallet_basic_pallet::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+    type Randomness = ParentHashRandomness;
+    type FindAuthor = SealAuthor;
+    type TimeProvider = Timestamp;
+    type MaxBlockClaims = MaxBlockClaims;
+    type RecentBlockWindow = RecentBlockWindow;
+    type MaxSharesPerBlock = MaxSharesPerBlock;
+    type InitialBlockReward = InitialBlockReward;
+    type HalvingInterval = HalvingInterval;
+    type TailEmission = TailEmission;
+    type CoinbaseMaturity = CoinbaseMaturity;
+    type RewardVestingPeriod = RewardVestingPeriod;
+    type MaxLockedRewards = MaxLockedRewards;
+    type MaxSupply = MaxSupply;
+    // Swap in `Lwma<LwmaWindow>` or `Asert<AsertHalfLife>` to change algorithm
+    type DifficultyAlgorithm = Periodic<DifficultyAdjustmentPeriod>;
+    type TargetBlockTime = TargetBlockTime;
+    type MedianTimeSpan = MedianTimeSpan;
+    type MaxFutureDrift = MaxFutureDrift;
+}
 
-/// Construct the runtime with our custom extrinsic type and signed extensions
+// Construct the runtime with our custom extrinsic type and signed extensions
 construct_runtime!(
-    pub enum Runtime where
-        Block = Block,
-        NodeBlock = Block,
-        UncheckedExtrinsic = UncheckedExtrinsic
-    {
-        System: frame_system::{Pallet, Call, Storage, Event<T>},
-        Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
-        BasicPallet: pallet_basic_pallet::{Pallet, Call, Storage, Event<T>},
+    pub enum Runtime {
+        System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
+        Balances: pallet_balances::{Pallet, Call, Config<T>, Storage, Event<T>},
+        BasicPallet: pallet_basic_pallet::{Pallet, Call, Config<T>, Storage, Event<T>},
     }
 );
 
 parameter_types! {
     pub const BlockHashCount: u32 = 2400;
-}
\ No newline at end of file
+    pub const Version: RuntimeVersion = VERSION;
+    /// Consecutive PoW blocks may be found milliseconds apart
+    pub const MinimumPeriod: u64 = 1;
+    /// Aim for one block every 12 seconds
+    pub const TargetBlockTime: u64 = coin_tokenomics::TARGET_BLOCK_TIME_MS;
+    /// Retarget every 100 blocks (20 minutes at the target block time)
+    pub const DifficultyAdjustmentPeriod: BlockNumber = 100;
+    /// LWMA averages the last 60 solve times (12 minutes at the target block time)
+    pub const LwmaWindow: u32 = 60;
+    /// ASERT halves or doubles the difficulty per hour off schedule
+    pub const AsertHalfLife: u64 = 60 * 60 * 1000;
+    /// Block rewards follow `coin_tokenomics::REWARD_SCHEDULE`, which the TypeScript
+    /// packages default to as well
+    pub const InitialBlockReward: Balance = REWARD_SCHEDULE.initial_reward;
+    pub const HalvingInterval: BlockNumber = REWARD_SCHEDULE.halving_interval as BlockNumber;
+    pub const TailEmission: Balance = REWARD_SCHEDULE.tail_emission;
+    pub const MaxSupply: Balance = REWARD_SCHEDULE.max_supply;
+    /// Rewards unlock 100 blocks after their block (20 minutes at the target block
+    /// time), deeper than any expected reorg
+    pub const CoinbaseMaturity: BlockNumber = 100;
+    /// and are then released at once
+    pub const RewardVestingPeriod: BlockNumber = 0;
+    /// Enough for a miner finding every block to claim once per maturity period
+    pub const MaxLockedRewards: u32 = 256;
+    pub const MaxBlockClaims: u32 = 32;
+    /// Shares must be mined against one of the last 64 blocks (about 13 minutes at
+    /// the target block time)
+    pub const RecentBlockWindow: u32 = 64;
+    /// Bounds the recorded shares to 64 * 1024 at any time
+    pub const MaxSharesPerBlock: u32 = 1024;
+    /// A block's time must exceed the median of this many preceding blocks
+    pub const MedianTimeSpan: u32 = 11;
+    /// A block's time may lead an importing node's clock by at most two block times
+    /// (`pallet_timestamp` separately allows at most 30 seconds)
+    pub const MaxFutureDrift: u64 = 2 * 12 * 1000;
+}
+
+impl_runtime_apis! {
+    impl sp_api::Core<Block> for Runtime {
+        fn version() -> RuntimeVersion {
+            VERSION
+        }
+
+        fn execute_block(block: Block) {
+            Executive::execute_block(block);
+        }
+
+        fn initialize_block(header: &<Block as BlockT>::Header) {
+            Executive::initialize_block(header)
+        }
+    }
+
+    impl sp_api::Metadata<Block> for Runtime {
+        fn metadata() -> OpaqueMetadata {
+            OpaqueMetadata::new(Runtime::metadata().into())
+        }
+
+        fn metadata_at_version(version: u32) -> Option<OpaqueMetadata> {
+            Runtime::metadata_at_version(version)
+        }
+
+        fn metadata_versions() -> Vec<u32> {
+            Runtime::metadata_versions()
+        }
+    }
+
+    impl sp_block_builder::BlockBuilder<Block> for Runtime {
+        fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyExtrinsicResult {
+            Executive::apply_extrinsic(extrinsic)
+        }
+
+        fn finalize_block() -> <Block as BlockT>::Header {
+            Executive::finalize_block()
+        }
+
+        fn inherent_extrinsics(data: sp_inherents::InherentData) -> Vec<<Block as BlockT>::Extrinsic> {
+            data.create_extrinsics()
+        }
+
+        fn check_inherents(
+            block: Block,
+            data: sp_inherents::InherentData,
+        ) -> sp_inherents::CheckInherentsResult {
+            let mut result = data.check_extrinsics(&block);
+            // The block time must also pass the PoW median-time-past and drift rules
+            for extrinsic in &block.extrinsics {
+                if let RuntimeCall::Timestamp(pallet_timestamp::Call::set { now }) = &extrinsic.function {
+                    if let Err(error) = BasicPallet::check_block_time(*now, &data) {
+                        // Only refused when a fatal error is already reported
+                        let _ = result.put_error(pow_primitives::time::INHERENT_IDENTIFIER, &error);
+                    }
+                }
+            }
+            result
+        }
+    }
+
+    impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
+        fn validate_transaction(
+            source: TransactionSource,
+            tx: <Block as BlockT>::Extrinsic,
+            block_hash: <Block as BlockT>::Hash,
+        ) -> TransactionValidity {
+            Executive::validate_transaction(source, tx, block_hash)
+        }
+    }
+
+    impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
+        fn offchain_worker(header: &<Block as BlockT>::Header) {
+            Executive::offchain_worker(header)
+        }
+    }
+
+    // The runtime has no session keys; this empty API only satisfies the
+    // `SessionKeys` bound of `sc_service::spawn_tasks`
+    impl sp_session::SessionKeys<Block> for Runtime {
+        fn generate_session_keys(_seed: Option<Vec<u8>>) -> Vec<u8> {
+            Vec::new()
+        }
+
+        fn decode_session_keys(_encoded: Vec<u8>) -> Option<Vec<(Vec<u8>, KeyTypeId)>> {
+            None
+        }
+    }
+
+    impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
+        fn account_nonce(account: AccountId) -> Nonce {
+            System::account_nonce(account)
+        }
+    }
+
+    #[api_version(2)]
+    impl pallet_basic_pallet_runtime_api::MiningApi<Block, AccountId, Balance, BlockNumber, H256> for Runtime {
+        fn difficulty() -> u64 {
+            BasicPallet::current_difficulty()
+        }
+
+        fn target() -> U256 {
+            BasicPallet::target()
+        }
+
+        fn next_retarget_block() -> BlockNumber {
+            BasicPallet::next_retarget_block()
+        }
+
+        fn next_block_reward() -> Balance {
+            BasicPallet::calculate_block_reward()
+        }
+
+        fn pending_rewards(who: AccountId) -> Balance {
+            BasicPallet::mining_rewards(who)
+        }
+
+        fn matured_rewards(who: AccountId) -> Balance {
+            BasicPallet::matured_rewards(&who)
+        }
+
+        fn verify_work(who: AccountId, nonce: Vec<u8>, block_hash: H256) -> bool {
+            BasicPallet::verify_proof_of_work(&who, &nonce, block_hash).is_ok()
+        }
+    }
+
+    impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
+        fn create_default_config() -> Vec<u8> {
+            create_default_config::<RuntimeGenesisConfig>()
+        }
+
+        fn build_config(config: Vec<u8>) -> sp_genesis_builder::Result {
+            build_config::<RuntimeGenesisConfig>(config)
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Balances of ML-DSA-derived accounts: genesis endowment, transfers, the
+//! existential deposit and locks.
+
+mod common;
+
+use common::{alice, bob, endowed_ext, ALICE_KEY};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{LockableCurrency, WithdrawReasons},
+};
+use runtime::{
+    AccountId, Balance, Balances, Runtime, RuntimeEvent, RuntimeOrigin, System,
+    EXISTENTIAL_DEPOSIT, UNIT,
+};
+use sp_runtime::TokenError;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut ext = endowed_ext(vec![(alice(), 100 * UNIT)]);
+    // Events are only recorded from block 1 on
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+#[test]
+fn accounts_are_blake3_of_the_public_key() {
+    assert_eq!(
+        alice(),
+        AccountId::new(*blake3::hash(&ALICE_KEY).as_bytes())
+    );
+    assert_ne!(alice(), bob());
+}
+
+#[test]
+fn genesis_endows_accounts() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Balances::free_balance(alice()), 100 * UNIT);
+        assert_eq!(Balances::free_balance(bob()), 0);
+        assert_eq!(Balances::total_issuance(), 100 * UNIT);
+    });
+}
+
+#[test]
+fn transfer_moves_funds() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(alice()),
+            bob(),
+            UNIT
+        ));
+        assert_eq!(Balances::free_balance(alice()), 99 * UNIT);
+        assert_eq!(Balances::free_balance(bob()), UNIT);
+        assert_eq!(Balances::total_issuance(), 100 * UNIT);
+    });
+}
+
+#[test]
+fn new_account_needs_the_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Balances::transfer_allow_death(
+                RuntimeOrigin::signed(alice()),
+                bob(),
+                EXISTENTIAL_DEPOSIT - 1
+            ),
+            TokenError::BelowMinimum
+        );
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(alice()),
+            bob(),
+            EXISTENTIAL_DEPOSIT
+        ));
+        assert_eq!(Balances::free_balance(bob()), EXISTENTIAL_DEPOSIT);
+    });
+}
+
+#[test]
+fn dust_of_reaped_account_is_burnt() {
+    new_test_ext().execute_with(|| {
+        let dust = EXISTENTIAL_DEPOSIT - 1;
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(alice()),
+            bob(),
+            100 * UNIT - dust
+        ));
+        assert_eq!(Balances::free_balance(alice()), 0);
+        assert!(!System::account_exists(&alice()));
+        assert_eq!(Balances::total_issuance(), 100 * UNIT - dust);
+        assert!(System::events().iter().any(|record| record.event
+            == RuntimeEvent::Balances(pallet_balances::Event::DustLost {
+                account: alice(),
+                amount: dust,
+            })));
+    });
+}
+
+#[test]
+fn keep_alive_transfer_cannot_reap_the_sender() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Balances::transfer_keep_alive(RuntimeOrigin::signed(alice()), bob(), 100 * UNIT),
+            TokenError::NotExpendable
+        );
+    });
+}
+
+#[test]
+fn locked_funds_cannot_be_transferred() {
+    new_test_ext().execute_with(|| {
+        Balances::set_lock(*b"testlock", &alice(), 60 * UNIT, WithdrawReasons::all());
+        assert_noop!(
+            Balances::transfer_allow_death(RuntimeOrigin::signed(alice()), bob(), 50 * UNIT),
+            TokenError::Frozen
+        );
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(alice()),
+            bob(),
+            40 * UNIT
+        ));
+
+        Balances::remove_lock(*b"testlock", &alice());
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(alice()),
+            bob(),
+            50 * UNIT
+        ));
+    });
+}
+
+#[test]
+fn limits_are_configured() {
+    use frame_support::traits::Get;
+    let existential_deposit: Balance =
+        <Runtime as pallet_balances::Config>::ExistentialDeposit::get();
+    assert_eq!(existential_deposit, EXISTENTIAL_DEPOSIT);
+    let max_locks: u32 = <Runtime as pallet_balances::Config>::MaxLocks::get();
+    let max_reserves: u32 = <Runtime as pallet_balances::Config>::MaxReserves::get();
+    let max_freezes: u32 = <Runtime as pallet_balances::Config>::MaxFreezes::get();
+    assert_eq!((max_locks, max_reserves, max_freezes), (50, 50, 8));
+}
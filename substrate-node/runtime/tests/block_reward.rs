@@ -0,0 +1,95 @@
+//! Block rewards halve on schedule down to the tail emission, and stop at the
+//! supply cap.
+
+mod common;
+
+use common::{mined_by, new_test_ext, MINER};
+use frame_support::traits::Hooks;
+use pallet_basic_pallet::{FixedDifficulty, MiningRewards, TotalMinted};
+use runtime::{
+    BasicPallet, HalvingInterval, InitialBlockReward, MaxSupply, Runtime, System, TailEmission,
+    Timestamp, UNIT,
+};
+use sp_runtime::Digest;
+
+/// Runs block `number`, mined by `MINER`, and starts the next one, which pays it.
+fn mine_block(number: u32) {
+    let parent_hash = System::block_hash(number - 1);
+    System::initialize(&number, &parent_hash, &mined_by(&MINER));
+    BasicPallet::on_initialize(number);
+    Timestamp::set_timestamp(u64::from(number) * 12_000);
+    BasicPallet::on_finalize(number);
+    let hash = System::finalize().hash();
+
+    System::initialize(&(number + 1), &hash, &Digest::default());
+    BasicPallet::on_initialize(number + 1);
+}
+
+#[test]
+fn reward_halves_every_interval() {
+    new_test_ext().execute_with(|| {
+        let interval: u32 = HalvingInterval::get();
+        assert_eq!(InitialBlockReward::get(), 888_888 * UNIT);
+        assert_eq!(BasicPallet::block_reward(1), 888_888 * UNIT);
+        assert_eq!(BasicPallet::block_reward(interval - 1), 888_888 * UNIT);
+        assert_eq!(BasicPallet::block_reward(interval), 444_444 * UNIT);
+        assert_eq!(BasicPallet::block_reward(3 * interval), 111_111 * UNIT);
+    });
+}
+
+#[test]
+fn tail_emission_follows_the_halvings() {
+    new_test_ext().execute_with(|| {
+        let interval: u32 = HalvingInterval::get();
+        assert!(BasicPallet::block_reward(19 * interval) > TailEmission::get());
+        assert_eq!(
+            BasicPallet::block_reward(20 * interval),
+            TailEmission::get()
+        );
+        assert_eq!(BasicPallet::block_reward(u32::MAX), TailEmission::get());
+    });
+}
+
+#[test]
+fn next_block_reward_is_for_the_next_height() {
+    new_test_ext().execute_with(|| {
+        let interval: u32 = HalvingInterval::get();
+        System::set_block_number(interval - 1);
+        assert_eq!(BasicPallet::calculate_block_reward(), 444_444 * UNIT);
+    });
+}
+
+#[test]
+fn credited_rewards_are_tracked() {
+    new_test_ext().execute_with(|| {
+        FixedDifficulty::<Runtime>::put(true);
+        mine_block(1);
+        assert_eq!(TotalMinted::<Runtime>::get(), 888_888 * UNIT);
+
+        // Paid at the height of the mined block
+        let interval: u32 = HalvingInterval::get();
+        mine_block(interval + 1);
+        assert_eq!(TotalMinted::<Runtime>::get(), (888_888 + 444_444) * UNIT);
+        assert_eq!(
+            MiningRewards::<Runtime>::get(MINER),
+            (888_888 + 444_444) * UNIT
+        );
+    });
+}
+
+#[test]
+fn rewards_stop_at_the_supply_cap() {
+    new_test_ext().execute_with(|| {
+        FixedDifficulty::<Runtime>::put(true);
+        TotalMinted::<Runtime>::put(MaxSupply::get() - 10);
+        assert_eq!(BasicPallet::calculate_block_reward(), 10);
+
+        mine_block(1);
+        assert_eq!(MiningRewards::<Runtime>::get(MINER), 10);
+        assert_eq!(TotalMinted::<Runtime>::get(), MaxSupply::get());
+
+        mine_block(2);
+        assert_eq!(MiningRewards::<Runtime>::get(MINER), 10);
+        assert_eq!(BasicPallet::calculate_block_reward(), 0);
+    });
+}
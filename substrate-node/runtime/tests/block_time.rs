@@ -0,0 +1,195 @@
+//! Median-time-past and future-drift rules on the timestamp inherent, as seen by
+//! block authors and importers.
+
+use frame_support::traits::Hooks;
+use pow_primitives::time::{InherentError, INHERENT_IDENTIFIER};
+use runtime::{
+    BasicPallet, Block, Header, MaxFutureDrift, MedianTimeSpan, Runtime, RuntimeCall,
+    RuntimeGenesisConfig, System, Timestamp, UncheckedExtrinsic,
+};
+use sp_block_builder::runtime_decl_for_block_builder::BlockBuilder;
+use sp_inherents::InherentData;
+use sp_runtime::{traits::Header as _, BuildStorage, Digest};
+
+const NOW: u64 = 1_700_000_000_000;
+const BLOCK_TIME: u64 = 60_000;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    RuntimeGenesisConfig::default()
+        .build_storage()
+        .unwrap()
+        .into()
+}
+
+/// Imports block `number` stamped with `time`.
+fn import_block(number: u32, time: u64) {
+    System::initialize(&number, &System::block_hash(number - 1), &Digest::default());
+    BasicPallet::on_initialize(number);
+    Timestamp::set_timestamp(time);
+    BasicPallet::on_finalize(number);
+    System::finalize();
+}
+
+/// Imports a full median window of honest blocks and returns the median time.
+fn honest_chain() -> u64 {
+    let span = MedianTimeSpan::get();
+    for number in 1..=span {
+        import_block(number, NOW + u64::from(number) * BLOCK_TIME);
+    }
+    NOW + u64::from(span / 2 + 1) * BLOCK_TIME
+}
+
+/// Inherent data of a node whose clock reads `time`.
+fn local_time(time: u64) -> InherentData {
+    let mut data = InherentData::new();
+    data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &time)
+        .unwrap();
+    data.put_data(INHERENT_IDENTIFIER, &time).unwrap();
+    data
+}
+
+/// Next block, carrying just the timestamp inherent for `time`.
+fn stamped_block(time: u64) -> Block {
+    let number = System::block_number() + 1;
+    let header = Header::new(
+        number,
+        Default::default(),
+        Default::default(),
+        System::block_hash(number - 1),
+        Digest::default(),
+    );
+    let call = RuntimeCall::Timestamp(pallet_timestamp::Call::set { now: time });
+    Block {
+        header,
+        extrinsics: vec![UncheckedExtrinsic::new_unsigned(call)],
+    }
+}
+
+/// Checks a block stamped with `time` against the importer's `data`, returning
+/// whether it passes and the PoW block time error, if any.
+fn check(time: u64, data: InherentData) -> (bool, Option<InherentError>) {
+    let result = <Runtime as BlockBuilder<Block>>::check_inherents(stamped_block(time), data);
+    (result.ok(), result.get_error(&INHERENT_IDENTIFIER).unwrap())
+}
+
+#[test]
+fn honest_block_passes_inherent_checks() {
+    new_test_ext().execute_with(|| {
+        honest_chain();
+        let next = NOW + 12 * BLOCK_TIME;
+        assert_eq!(check(next, local_time(next)), (true, None));
+        // Slightly fast clocks are tolerated
+        assert_eq!(
+            check(next + MaxFutureDrift::get(), local_time(next)),
+            (true, None)
+        );
+    });
+}
+
+#[test]
+fn block_too_far_in_future_is_rejected() {
+    new_test_ext().execute_with(|| {
+        honest_chain();
+        let local = NOW + 12 * BLOCK_TIME;
+        let time = local + MaxFutureDrift::get() + 1;
+        assert_eq!(
+            check(time, local_time(local)),
+            (
+                false,
+                Some(InherentError::TooFarInFuture {
+                    timestamp: time,
+                    local_time: local,
+                    max_drift: MaxFutureDrift::get(),
+                })
+            )
+        );
+    });
+}
+
+#[test]
+fn block_not_after_median_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let median = honest_chain();
+        let data = local_time(NOW + 12 * BLOCK_TIME);
+        assert_eq!(
+            BasicPallet::check_block_time(median, &data),
+            Err(InherentError::NotAfterMedianTimePast {
+                timestamp: median,
+                median
+            })
+        );
+        assert_eq!(BasicPallet::check_block_time(median + 1, &data), Ok(()));
+        // `pallet_timestamp` already refuses anything before the previous block
+        assert!(!check(median + 1, data).0);
+    });
+}
+
+#[test]
+fn block_without_local_time_cannot_be_checked() {
+    new_test_ext().execute_with(|| {
+        let mut data = InherentData::new();
+        data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &NOW)
+            .unwrap();
+        assert_eq!(
+            check(NOW, data),
+            (false, Some(InherentError::MissingLocalTime))
+        );
+    });
+}
+
+#[test]
+fn time_warp_series_is_rejected() {
+    // An attacker mining consecutive blocks stamps each one as early as the chain
+    // allows, trying to make the retarget window look short. The chain's clock still
+    // moves forward, and no stamp at or below the median passes.
+    new_test_ext().execute_with(|| {
+        honest_chain();
+        let mut previous_median = 0;
+        for number in MedianTimeSpan::get() + 1..200 {
+            let recent = BasicPallet::recent_timestamps();
+            let median = pow_primitives::time::median_time_past(&recent).unwrap();
+            assert!(median > previous_median);
+            previous_median = median;
+
+            let local = NOW + u64::from(number) * BLOCK_TIME;
+            for warped in [0, median / 2, median] {
+                assert!(!check(warped, local_time(local)).0);
+            }
+            let earliest = Timestamp::now() + 1;
+            assert_eq!(check(earliest, local_time(local)), (true, None));
+            import_block(number, earliest);
+        }
+        assert_eq!(
+            BasicPallet::recent_timestamps().len() as u32,
+            MedianTimeSpan::get()
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "The block time must be later than the median time of recent blocks")]
+fn block_not_after_median_fails_execution() {
+    new_test_ext().execute_with(|| {
+        let median = honest_chain();
+        import_block(MedianTimeSpan::get() + 1, median);
+    });
+}
+
+#[test]
+fn author_with_slow_clock_stamps_after_previous_block() {
+    new_test_ext().execute_with(|| {
+        let median = honest_chain();
+        let extrinsics = <Runtime as BlockBuilder<Block>>::inherent_extrinsics(local_time(0));
+        let previous = NOW + u64::from(MedianTimeSpan::get()) * BLOCK_TIME;
+        assert_eq!(
+            extrinsics
+                .iter()
+                .map(|extrinsic| extrinsic.function.clone())
+                .collect::<Vec<_>>(),
+            vec![RuntimeCall::Timestamp(pallet_timestamp::Call::set {
+                now: previous + 1
+            })]
+        );
+        assert!(previous + 1 > median);
+    });
+}
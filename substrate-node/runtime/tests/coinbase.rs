@@ -0,0 +1,78 @@
+//! The miner named in a block's pre-runtime digest is paid exactly once for it.
+
+mod common;
+
+use common::{mined_by, new_test_ext, MINER};
+use frame_support::traits::Hooks;
+use pallet_basic_pallet::{BlockAuthor, Event as MiningEvent, MiningRewards};
+use runtime::{BasicPallet, Runtime, RuntimeEvent, System, Timestamp};
+use sp_core::H256;
+use sp_runtime::{generic::DigestItem, Digest};
+
+/// Starts block `number` on `parent_hash` like `Executive::initialize_block`, with
+/// `digest`.
+fn start_block(number: u32, parent_hash: H256, digest: Digest) {
+    System::reset_events();
+    System::initialize(&number, &parent_hash, &digest);
+    BasicPallet::on_initialize(number);
+}
+
+/// Sets the block time, finishes the current block and returns its hash.
+fn finish_block() -> H256 {
+    let number = System::block_number();
+    Timestamp::set_timestamp(u64::from(number) * 60_000);
+    BasicPallet::on_finalize(number);
+    System::finalize().hash()
+}
+
+fn block_mined_events() -> Vec<MiningEvent<Runtime>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::BasicPallet(event @ MiningEvent::BlockMined(..)) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn author_is_paid_once_with_the_real_block_hash() {
+    new_test_ext().execute_with(|| {
+        let reward = BasicPallet::calculate_block_reward();
+
+        start_block(1, H256::zero(), mined_by(&MINER));
+        let difficulty = BasicPallet::current_difficulty();
+        let hash = finish_block();
+        // Block 1's hash is only known once it is finalized
+        assert_eq!(MiningRewards::<Runtime>::get(MINER), 0);
+
+        start_block(2, hash, Digest::default());
+        assert_eq!(MiningRewards::<Runtime>::get(MINER), reward);
+        assert_eq!(
+            block_mined_events(),
+            vec![MiningEvent::BlockMined(MINER, hash, difficulty)]
+        );
+        // Block 2 has no author to pay later
+        assert_eq!(BlockAuthor::<Runtime>::get(), None);
+        let hash = finish_block();
+
+        start_block(3, hash, mined_by(&MINER));
+        assert_eq!(MiningRewards::<Runtime>::get(MINER), reward);
+        assert!(block_mined_events().is_empty());
+    });
+}
+
+#[test]
+fn malformed_pre_digest_earns_nothing() {
+    new_test_ext().execute_with(|| {
+        let mut digest = mined_by(&MINER);
+        if let DigestItem::PreRuntime(_, data) = &mut digest.logs[0] {
+            data.push(0);
+        }
+        start_block(1, H256::zero(), digest);
+        let hash = finish_block();
+        start_block(2, hash, Digest::default());
+        assert_eq!(MiningRewards::<Runtime>::get(MINER), 0);
+        assert!(block_mined_events().is_empty());
+    });
+}
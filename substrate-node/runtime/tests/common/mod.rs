@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+
+use frame_support::{dispatch::DispatchResult, traits::Hooks};
+use pallet_basic_pallet::BlockAuthor;
+use parity_scale_codec::Encode;
+use pow_primitives::{Algorithm, PreDigest, ENGINE_ID};
+use runtime::{
+    account_id, AccountId, Balance, BasicPallet, Runtime, RuntimeGenesisConfig, RuntimeOrigin,
+    System,
+};
+use sp_runtime::{generic::DigestItem, BuildStorage, Digest};
+
+pub const MINER: AccountId = AccountId::new([5; 32]);
+
+/// Stand-ins for encoded ML-DSA public keys
+pub const ALICE_KEY: [u8; 64] = [1; 64];
+pub const BOB_KEY: [u8; 64] = [2; 64];
+
+pub fn alice() -> AccountId {
+    account_id(&ALICE_KEY)
+}
+
+pub fn bob() -> AccountId {
+    account_id(&BOB_KEY)
+}
+
+/// The default genesis: no endowments, retargeting from difficulty 1,000,000.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    RuntimeGenesisConfig::default()
+        .build_storage()
+        .unwrap()
+        .into()
+}
+
+/// The default genesis with `balances` endowed.
+pub fn endowed_ext(balances: Vec<(AccountId, Balance)>) -> sp_io::TestExternalities {
+    let config = RuntimeGenesisConfig {
+        balances: pallet_balances::GenesisConfig { balances },
+        ..Default::default()
+    };
+    config.build_storage().unwrap().into()
+}
+
+/// Pre-runtime digest naming `miner`, as the node's miner writes it.
+pub fn mined_by(miner: &AccountId) -> Digest {
+    let pre_digest = PreDigest {
+        algorithm: Algorithm::Blake3V2,
+        miner: miner.clone().into(),
+    };
+    Digest {
+        logs: vec![DigestItem::PreRuntime(ENGINE_ID, pre_digest.encode())],
+    }
+}
+
+/// Credits `MINER` with the reward of block `number`, as the next block does.
+pub fn credit(number: u32) {
+    BlockAuthor::<Runtime>::put((MINER, 1));
+    System::set_block_number(number + 1);
+    BasicPallet::on_initialize(number + 1);
+}
+
+pub fn claim() -> DispatchResult {
+    BasicPallet::claim_rewards(RuntimeOrigin::signed(MINER))
+}
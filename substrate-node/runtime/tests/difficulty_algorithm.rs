@@ -0,0 +1,119 @@
+//! Bookkeeping around the configured difficulty algorithm: the records it reads and
+//! the migration that hands over between algorithms.
+
+use frame_support::traits::{Hooks, OnRuntimeUpgrade};
+use pallet_basic_pallet::{
+    difficulty::AlgorithmKind, migrations::SwitchDifficultyAlgorithm, ActiveDifficultyAlgorithm,
+    AsertAnchor, Event as MiningEvent,
+};
+use runtime::{
+    BasicPallet, DifficultyAdjustmentPeriod, Runtime, RuntimeEvent, RuntimeGenesisConfig, System,
+    TargetBlockTime, Timestamp,
+};
+use sp_runtime::BuildStorage;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    RuntimeGenesisConfig::default()
+        .build_storage()
+        .unwrap()
+        .into()
+}
+
+/// Mines blocks up to `last`, on target.
+fn mine_until(last: u32) {
+    for number in System::block_number() + 1..=last {
+        System::set_block_number(number);
+        BasicPallet::on_initialize(number);
+        Timestamp::set_timestamp(u64::from(number) * TargetBlockTime::get());
+        BasicPallet::on_finalize(number);
+    }
+}
+
+#[test]
+fn genesis_records_the_configured_algorithm() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            BasicPallet::active_difficulty_algorithm(),
+            AlgorithmKind::Periodic
+        );
+    });
+}
+
+#[test]
+fn every_block_records_its_difficulty() {
+    new_test_ext().execute_with(|| {
+        mine_until(3);
+        for number in 1..=3 {
+            assert_eq!(BasicPallet::block_difficulties(number), Some(1_000_000));
+            assert_eq!(
+                BasicPallet::block_timestamps(number),
+                u64::from(number) * TargetBlockTime::get()
+            );
+        }
+    });
+}
+
+#[test]
+fn records_before_the_current_window_are_pruned() {
+    new_test_ext().execute_with(|| {
+        let period = DifficultyAdjustmentPeriod::get();
+        mine_until(2 * period + 10);
+        assert_eq!(BasicPallet::last_difficulty_adjustment(), 2 * period);
+        // The window running since the last retarget is kept in full
+        assert!((2 * period..=2 * period + 10)
+            .all(|number| BasicPallet::block_difficulties(number).is_some()));
+        assert!((1..period + 10).all(|number| BasicPallet::block_difficulties(number).is_none()));
+    });
+}
+
+#[test]
+fn unchanged_algorithm_needs_no_migration() {
+    new_test_ext().execute_with(|| {
+        mine_until(150);
+        SwitchDifficultyAlgorithm::<Runtime>::on_runtime_upgrade();
+        assert_eq!(
+            BasicPallet::last_difficulty_adjustment(),
+            DifficultyAdjustmentPeriod::get()
+        );
+        assert!(BasicPallet::block_difficulties(100).is_some());
+    });
+}
+
+#[test]
+fn switching_algorithm_restarts_from_the_parent_block() {
+    new_test_ext().execute_with(|| {
+        // As if the chain had been running ASERT until now
+        ActiveDifficultyAlgorithm::<Runtime>::put(AlgorithmKind::Asert);
+        mine_until(150);
+        AsertAnchor::<Runtime>::put(pallet_basic_pallet::difficulty::Anchor {
+            height: 1,
+            timestamp: TargetBlockTime::get(),
+            difficulty: 1_000_000,
+        });
+
+        SwitchDifficultyAlgorithm::<Runtime>::on_runtime_upgrade();
+
+        assert_eq!(
+            BasicPallet::active_difficulty_algorithm(),
+            AlgorithmKind::Periodic
+        );
+        assert_eq!(BasicPallet::asert_anchor(), None);
+        assert_eq!(BasicPallet::last_difficulty_adjustment(), 150);
+        assert_eq!(
+            BasicPallet::next_retarget_block(),
+            150 + DifficultyAdjustmentPeriod::get()
+        );
+        assert!((50..=150).all(|number| BasicPallet::block_difficulties(number).is_some()));
+        assert!((1..50).all(|number| BasicPallet::block_difficulties(number).is_none()));
+        assert!(System::events().iter().any(|record| record.event
+            == RuntimeEvent::BasicPallet(MiningEvent::DifficultyAlgorithmSwitched(
+                AlgorithmKind::Asert,
+                AlgorithmKind::Periodic
+            ))));
+
+        // The first window under the new algorithm runs from the parent block
+        mine_until(250);
+        assert_eq!(BasicPallet::last_difficulty_adjustment(), 250);
+        assert_eq!(BasicPallet::current_difficulty(), 1_000_000);
+    });
+}
@@ -0,0 +1,116 @@
+//! Claimed rewards are minted, and the emission ledger accounts for the total
+//! issuance.
+
+mod common;
+
+use common::{alice, claim, credit, endowed_ext, MINER};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{fungible::Mutate, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use pallet_basic_pallet::{
+    migrations::StartEmissionLedger, rewards::LockedReward, Emission, LockedRewards, MiningRewards,
+    TotalMinted,
+};
+use runtime::{Balances, BasicPallet, Runtime, RuntimeOrigin, System, EXISTENTIAL_DEPOSIT, UNIT};
+use sp_runtime::TokenError;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    endowed_ext(vec![(alice(), 100 * UNIT)])
+}
+
+#[test]
+fn genesis_issuance_is_on_the_ledger() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(BasicPallet::emission().genesis, 100 * UNIT);
+        assert_eq!(
+            BasicPallet::emission().issuance(),
+            Balances::total_issuance()
+        );
+        assert_ok!(BasicPallet::check_emission_ledger());
+    });
+}
+
+#[test]
+fn claimed_rewards_are_minted() {
+    new_test_ext().execute_with(|| {
+        let reward = BasicPallet::block_reward(1);
+        credit(1);
+        // Credited, not minted
+        assert_eq!(Balances::total_issuance(), 100 * UNIT);
+        assert_ok!(BasicPallet::check_emission_ledger());
+
+        System::set_block_number(101);
+        assert_ok!(claim());
+        assert_eq!(Balances::free_balance(MINER), reward);
+        assert_eq!(Balances::total_issuance(), 100 * UNIT + reward);
+        assert_eq!(BasicPallet::emission().minted, reward);
+        assert_ok!(BasicPallet::check_emission_ledger());
+    });
+}
+
+#[test]
+fn claim_that_cannot_be_minted_keeps_the_rewards() {
+    new_test_ext().execute_with(|| {
+        // Too little to create the miner's account
+        let dust = EXISTENTIAL_DEPOSIT - 1;
+        MiningRewards::<Runtime>::insert(MINER, dust);
+        TotalMinted::<Runtime>::put(dust);
+        LockedRewards::<Runtime>::insert(
+            MINER,
+            frame_support::BoundedVec::truncate_from(vec![LockedReward {
+                mined_at: 0,
+                amount: dust,
+                claimed: 0,
+            }]),
+        );
+        System::set_block_number(101);
+
+        assert_noop!(claim(), TokenError::BelowMinimum);
+        assert_eq!(BasicPallet::matured_rewards(&MINER), dust);
+        assert_ok!(BasicPallet::check_emission_ledger());
+    });
+}
+
+#[test]
+fn burned_dust_is_on_the_ledger() {
+    new_test_ext().execute_with(|| {
+        let dust = EXISTENTIAL_DEPOSIT - 1;
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(alice()),
+            MINER,
+            100 * UNIT - dust
+        ));
+        assert_eq!(Balances::total_issuance(), 100 * UNIT - dust);
+        assert_eq!(BasicPallet::emission().burned, dust);
+        assert_ok!(BasicPallet::check_emission_ledger());
+    });
+}
+
+#[test]
+fn ledger_of_version_2_counts_claimed_rewards_as_minted() {
+    new_test_ext().execute_with(|| {
+        // 25 UNIT credited, of which 15 were claimed
+        TotalMinted::<Runtime>::put(25 * UNIT);
+        MiningRewards::<Runtime>::insert(MINER, 10 * UNIT);
+        assert_ok!(Balances::mint_into(&MINER, 15 * UNIT));
+        Emission::<Runtime>::kill();
+        StorageVersion::new(2).put::<BasicPallet>();
+
+        StartEmissionLedger::<Runtime>::on_runtime_upgrade();
+
+        assert_eq!(BasicPallet::on_chain_storage_version(), 3);
+        assert_eq!(BasicPallet::emission().genesis, 100 * UNIT);
+        assert_eq!(BasicPallet::emission().minted, 15 * UNIT);
+        assert_eq!(
+            BasicPallet::emission().issuance(),
+            Balances::total_issuance()
+        );
+        assert_ok!(BasicPallet::check_emission_ledger());
+
+        // Later upgrades leave the ledger alone
+        Emission::<Runtime>::mutate(|ledger| ledger.burned = 1);
+        StartEmissionLedger::<Runtime>::on_runtime_upgrade();
+        assert_eq!(BasicPallet::emission().burned, 1);
+    });
+}
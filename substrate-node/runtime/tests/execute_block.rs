@@ -0,0 +1,98 @@
+//! Builds a block through the runtime API entry points and re-executes it in a
+//! fresh state, as an importing node would.
+
+use runtime::{Executive, Header, Runtime, RuntimeGenesisConfig, System, VERSION};
+use sp_api::{runtime_decl_for_core::Core, runtime_decl_for_metadata::Metadata};
+use sp_block_builder::runtime_decl_for_block_builder::BlockBuilder;
+use sp_inherents::InherentData;
+use sp_runtime::{generic::Digest, traits::Header as _, BuildStorage};
+
+const NOW: u64 = 1_700_000_000_000;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    RuntimeGenesisConfig::default()
+        .build_storage()
+        .unwrap()
+        .into()
+}
+
+/// Runs the block-builder half: initialize, apply the inherents, finalize.
+fn author_block(number: u32, parent_hash: sp_core::H256) -> runtime::Block {
+    let header = Header::new(
+        number,
+        Default::default(),
+        Default::default(),
+        parent_hash,
+        Digest::default(),
+    );
+    Executive::initialize_block(&header);
+    let mut data = InherentData::new();
+    data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &NOW)
+        .unwrap();
+    let extrinsics = <Runtime as BlockBuilder<runtime::Block>>::inherent_extrinsics(data);
+    for extrinsic in &extrinsics {
+        Executive::apply_extrinsic(extrinsic.clone())
+            .unwrap()
+            .unwrap();
+    }
+    let header = Executive::finalize_block();
+    runtime::Block { header, extrinsics }
+}
+
+#[test]
+fn authored_block_executes_in_fresh_state() {
+    let (block, parent_hash) = new_test_ext().execute_with(|| {
+        let parent_hash = System::parent_hash();
+        (author_block(1, parent_hash), parent_hash)
+    });
+    assert_eq!(*block.header.number(), 1);
+    assert_eq!(*block.header.parent_hash(), parent_hash);
+
+    // Executing checks the state and extrinsics roots recorded in the header
+    new_test_ext().execute_with(|| {
+        <Runtime as Core<runtime::Block>>::execute_block(block.clone());
+        assert_eq!(System::block_number(), 1);
+        assert_eq!(runtime::Timestamp::now(), NOW);
+        assert_eq!(runtime::BasicPallet::block_timestamps(1), NOW);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Storage root must match that calculated.")]
+fn block_with_wrong_state_root_is_rejected() {
+    let mut block = new_test_ext().execute_with(|| author_block(1, System::parent_hash()));
+    block.header.state_root = Default::default();
+    new_test_ext().execute_with(|| Executive::execute_block(block));
+}
+
+#[test]
+#[should_panic(expected = "Timestamp must be updated once in the block")]
+fn block_without_block_time_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let header = Header::new(
+            1,
+            Default::default(),
+            Default::default(),
+            System::parent_hash(),
+            Digest::default(),
+        );
+        Executive::initialize_block(&header);
+        Executive::finalize_block();
+    });
+}
+
+#[test]
+fn runtime_apis_report_version_and_metadata() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            <Runtime as Core<runtime::Block>>::version().spec_version,
+            VERSION.spec_version
+        );
+        assert!(<Runtime as Metadata<runtime::Block>>::metadata_versions().contains(&14));
+    });
+}
+
+#[test]
+fn wasm_binary_is_built() {
+    assert!(runtime::WASM_BINARY.is_some_and(|wasm| !wasm.is_empty()));
+}
@@ -0,0 +1,37 @@
+//! The runtime reads the miner from the same pre-runtime digest the node verifies.
+
+use frame_support::traits::FindAuthor;
+use parity_scale_codec::Encode;
+use pow_primitives::{Algorithm, PreDigest, ENGINE_ID};
+use runtime::{AccountId, SealAuthor};
+
+#[test]
+fn author_comes_from_pow_pre_digest() {
+    let miner = [9u8; 32];
+    let digest = PreDigest {
+        algorithm: Algorithm::Blake3V2,
+        miner,
+    }
+    .encode();
+    assert_eq!(
+        SealAuthor::find_author([(ENGINE_ID, &digest[..])]),
+        Some(AccountId::new(miner))
+    );
+}
+
+#[test]
+fn malformed_or_missing_digest_has_no_author() {
+    let digest = PreDigest {
+        algorithm: Algorithm::Blake3V2,
+        miner: [9; 32],
+    }
+    .encode();
+    let trailing = [&digest[..], &[0]].concat();
+    assert_eq!(SealAuthor::find_author([(ENGINE_ID, &trailing[..])]), None);
+    assert_eq!(SealAuthor::find_author([(ENGINE_ID, &digest[1..])]), None);
+    assert_eq!(SealAuthor::find_author([(*b"aura", &digest[..])]), None);
+    assert_eq!(
+        SealAuthor::find_author(Vec::<([u8; 4], &[u8])>::new()),
+        None
+    );
+}
@@ -0,0 +1,43 @@
+//! Genesis presets used by the node's chain specs.
+
+use frame_support::traits::Hooks;
+use runtime::{BasicPallet, RuntimeGenesisConfig, System, Timestamp};
+use sp_runtime::BuildStorage;
+
+#[test]
+fn default_genesis_retargets_from_the_default_difficulty() {
+    let mut ext: sp_io::TestExternalities = RuntimeGenesisConfig::default()
+        .build_storage()
+        .unwrap()
+        .into();
+    ext.execute_with(|| {
+        assert_eq!(BasicPallet::current_difficulty(), 1_000_000);
+        assert!(!BasicPallet::fixed_difficulty());
+    });
+}
+
+#[test]
+fn fixed_difficulty_never_retargets() {
+    // As in the node's dev sealing chain spec
+    let config = RuntimeGenesisConfig {
+        basic_pallet: pallet_basic_pallet::GenesisConfig {
+            initial_difficulty: 1,
+            fixed_difficulty: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut ext: sp_io::TestExternalities = config.build_storage().unwrap().into();
+    ext.execute_with(|| {
+        let period = BasicPallet::next_retarget_block();
+        for number in 1..=period * 3 {
+            System::set_block_number(number);
+            BasicPallet::on_initialize(number);
+            // Blocks a second apart would otherwise raise the difficulty
+            Timestamp::set_timestamp(u64::from(number) * 1_000);
+            BasicPallet::on_finalize(number);
+        }
+        assert_eq!(BasicPallet::current_difficulty(), 1);
+        assert_eq!(BasicPallet::last_difficulty_adjustment(), 0);
+    });
+}
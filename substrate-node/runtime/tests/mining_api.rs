@@ -0,0 +1,80 @@
+//! `MiningApi` answers from pallet state without changing it.
+
+use pallet_basic_pallet::{CurrentTarget, LastDifficultyAdjustment, MiningRewards};
+use pow_primitives::target::Target;
+// Single implementation, so the API's type parameters are inferred
+use pallet_basic_pallet_runtime_api::runtime_decl_for_mining_api::MiningApi as _;
+use pallet_basic_pallet_runtime_api::MiningApi;
+use runtime::{
+    AccountId, Balance, Block, BlockNumber, DifficultyAdjustmentPeriod, Runtime,
+    RuntimeGenesisConfig, VERSION,
+};
+use sp_api::RuntimeApiInfo;
+use sp_core::{H256, U256};
+use sp_runtime::BuildStorage;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    RuntimeGenesisConfig::default()
+        .build_storage()
+        .unwrap()
+        .into()
+}
+
+#[test]
+fn runtime_reports_version_2_with_matured_rewards() {
+    type Api = dyn MiningApi<Block, AccountId, Balance, BlockNumber, H256>;
+    assert_eq!(Api::VERSION, 2);
+    assert!(VERSION.apis.contains(&(Api::ID, 2)));
+}
+
+#[test]
+fn difficulty_is_derived_from_target() {
+    new_test_ext().execute_with(|| {
+        CurrentTarget::<Runtime>::put(Target::from_difficulty(1_000).unwrap());
+        assert_eq!(Runtime::difficulty(), 1_000);
+        assert_eq!(Runtime::target(), U256::MAX / 1_000);
+    });
+}
+
+#[test]
+fn next_retarget_follows_last_adjustment() {
+    new_test_ext().execute_with(|| {
+        LastDifficultyAdjustment::<Runtime>::put(40);
+        assert_eq!(
+            Runtime::next_retarget_block(),
+            40 + DifficultyAdjustmentPeriod::get()
+        );
+    });
+}
+
+#[test]
+fn pending_rewards_and_next_reward() {
+    new_test_ext().execute_with(|| {
+        let miner = AccountId::new([7; 32]);
+        assert_eq!(Runtime::pending_rewards(miner.clone()), 0);
+        MiningRewards::<Runtime>::insert(&miner, 1_234);
+        assert_eq!(Runtime::pending_rewards(miner.clone()), 1_234);
+        assert_eq!(
+            Runtime::next_block_reward(),
+            pallet_basic_pallet::Pallet::<Runtime>::calculate_block_reward()
+        );
+    });
+}
+
+#[test]
+fn verify_work_is_a_dry_run() {
+    new_test_ext().execute_with(|| {
+        let miner = AccountId::new([7; 32]);
+        let block_hash = H256::repeat_byte(1);
+
+        // Difficulty 1 accepts every hash
+        CurrentTarget::<Runtime>::put(Target::MAX);
+        assert!(Runtime::verify_work(miner.clone(), vec![0], block_hash));
+
+        // Only hashes 0 and 1 meet the hardest target
+        CurrentTarget::<Runtime>::put(Target::new(U256::one()).unwrap());
+        assert!(!Runtime::verify_work(miner.clone(), vec![0], block_hash));
+
+        assert_eq!(MiningRewards::<Runtime>::get(&miner), 0);
+    });
+}
@@ -0,0 +1,82 @@
+//! Periodic retargeting in the runtime, driven by the timestamp inherent.
+
+use frame_support::traits::Hooks;
+use pallet_basic_pallet::Event as MiningEvent;
+use runtime::{
+    BasicPallet, DifficultyAdjustmentPeriod, RuntimeEvent, RuntimeGenesisConfig, System,
+    TargetBlockTime, Timestamp,
+};
+use sp_runtime::BuildStorage;
+
+const INITIAL_DIFFICULTY: u64 = 1_000_000;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    RuntimeGenesisConfig::default()
+        .build_storage()
+        .unwrap()
+        .into()
+}
+
+/// Mines one retarget window with blocks `block_time` apart, counting time from
+/// genesis at 0, and returns the difficulty afterwards.
+fn mine_window(block_time: u64) -> u64 {
+    let start = System::block_number();
+    let start_time = Timestamp::now();
+    for offset in 1..=DifficultyAdjustmentPeriod::get() {
+        let number = start + offset;
+        System::set_block_number(number);
+        BasicPallet::on_initialize(number);
+        Timestamp::set_timestamp(start_time + u64::from(offset) * block_time);
+        BasicPallet::on_finalize(number);
+    }
+    BasicPallet::current_difficulty()
+}
+
+#[test]
+fn on_target_window_keeps_difficulty() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(mine_window(TargetBlockTime::get()), INITIAL_DIFFICULTY);
+        assert_eq!(
+            BasicPallet::last_difficulty_adjustment(),
+            DifficultyAdjustmentPeriod::get()
+        );
+    });
+}
+
+#[test]
+fn fast_window_raises_difficulty_proportionally() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            mine_window(TargetBlockTime::get() / 2),
+            2 * INITIAL_DIFFICULTY
+        );
+        assert!(System::events().iter().any(|record| record.event
+            == RuntimeEvent::BasicPallet(MiningEvent::DifficultyAdjusted(
+                INITIAL_DIFFICULTY,
+                2 * INITIAL_DIFFICULTY
+            ))));
+    });
+}
+
+#[test]
+fn adjustment_is_clamped_to_fourfold() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(mine_window(1), 4 * INITIAL_DIFFICULTY);
+        assert_eq!(
+            mine_window(TargetBlockTime::get() * 100),
+            INITIAL_DIFFICULTY
+        );
+        assert_eq!(
+            mine_window(TargetBlockTime::get() * 100),
+            INITIAL_DIFFICULTY / 4
+        );
+    });
+}
+
+#[test]
+fn new_difficulty_is_rounded_down() {
+    new_test_ext().execute_with(|| {
+        // 1_000_000 * 12_000 / 7_001 = 1_714_040.85...
+        assert_eq!(mine_window(7_001), 1_714_040);
+    });
+}
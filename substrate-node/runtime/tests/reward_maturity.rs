@@ -0,0 +1,101 @@
+//! Block rewards unlock only once their block has matured, then vest.
+
+mod common;
+
+use common::{claim, credit, new_test_ext, MINER};
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use pallet_basic_pallet::{
+    migrations::LockRewardsPerBlock, rewards::LockedReward, Error, LockedRewards, MiningRewards,
+};
+// Single implementation, so the API's type parameters are inferred
+use pallet_basic_pallet_runtime_api::runtime_decl_for_mining_api::MiningApi as _;
+use runtime::{Balances, BasicPallet, MaxLockedRewards, Runtime, System, UNIT};
+
+#[test]
+fn rewards_are_locked_until_their_block_matures() {
+    new_test_ext().execute_with(|| {
+        let reward = BasicPallet::block_reward(1);
+        credit(1);
+        assert_eq!(Runtime::pending_rewards(MINER.clone()), reward);
+        assert_eq!(Runtime::matured_rewards(MINER.clone()), 0);
+        assert_noop!(claim(), Error::<Runtime>::NoRewardsToClaim);
+
+        // Block 1 matures 100 blocks later
+        System::set_block_number(100);
+        assert_noop!(claim(), Error::<Runtime>::NoRewardsToClaim);
+        System::set_block_number(101);
+        assert_eq!(Runtime::matured_rewards(MINER.clone()), reward);
+
+        assert_ok!(claim());
+        assert_eq!(Balances::free_balance(MINER), reward);
+        assert_eq!(Runtime::pending_rewards(MINER.clone()), 0);
+        assert!(!LockedRewards::<Runtime>::contains_key(MINER));
+    });
+}
+
+#[test]
+fn each_block_reward_matures_on_its_own() {
+    new_test_ext().execute_with(|| {
+        let (first, second) = (BasicPallet::block_reward(1), BasicPallet::block_reward(50));
+        credit(1);
+        credit(50);
+
+        System::set_block_number(120);
+        assert_eq!(Runtime::matured_rewards(MINER.clone()), first);
+        assert_ok!(claim());
+        assert_eq!(Balances::free_balance(MINER), first);
+        assert_eq!(Runtime::pending_rewards(MINER.clone()), second);
+        assert_noop!(claim(), Error::<Runtime>::NoRewardsToClaim);
+
+        System::set_block_number(150);
+        assert_ok!(claim());
+        assert_eq!(Balances::free_balance(MINER), first + second);
+        assert_eq!(MiningRewards::<Runtime>::get(MINER), 0);
+    });
+}
+
+#[test]
+fn rewards_beyond_the_bound_join_the_newest() {
+    new_test_ext().execute_with(|| {
+        let max: u32 = MaxLockedRewards::get();
+        for number in 1..=max + 1 {
+            credit(number);
+        }
+        let locked = LockedRewards::<Runtime>::get(MINER);
+        assert_eq!(locked.len() as u32, max);
+        let newest = locked.last().unwrap();
+        assert_eq!(newest.mined_at, max + 1);
+        assert_eq!(
+            newest.amount,
+            BasicPallet::block_reward(max) + BasicPallet::block_reward(max + 1)
+        );
+        let total: u128 = locked.iter().map(|reward| reward.amount).sum();
+        assert_eq!(total, MiningRewards::<Runtime>::get(MINER));
+    });
+}
+
+#[test]
+fn unclaimed_rewards_of_version_1_are_locked_at_genesis() {
+    new_test_ext().execute_with(|| {
+        MiningRewards::<Runtime>::insert(MINER, 10 * UNIT);
+        StorageVersion::new(1).put::<BasicPallet>();
+
+        LockRewardsPerBlock::<Runtime>::on_runtime_upgrade();
+
+        assert_eq!(BasicPallet::on_chain_storage_version(), 2);
+        assert_eq!(
+            LockedRewards::<Runtime>::get(MINER).into_inner(),
+            vec![LockedReward {
+                mined_at: 0,
+                amount: 10 * UNIT,
+                claimed: 0
+            }]
+        );
+        System::set_block_number(100);
+        assert_ok!(claim());
+        assert_eq!(Balances::free_balance(MINER), 10 * UNIT);
+    });
+}
@@ -0,0 +1,194 @@
+//! Side-pool shares must be new work mined against a recent block.
+
+mod common;
+
+use common::MINER;
+use frame_support::{assert_noop, assert_ok, dispatch::DispatchResult, traits::Hooks};
+use pallet_basic_pallet::{CurrentTarget, Error, SolvedWork, SolvedWorkCount};
+use pow_primitives::target::Target;
+use runtime::{
+    AccountId, BasicPallet, MaxBlockClaims, MaxSharesPerBlock, RecentBlockWindow, Runtime,
+    RuntimeOrigin, System, Timestamp,
+};
+use sp_core::H256;
+use sp_runtime::Digest;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut ext = common::new_test_ext();
+    // Every hash meets the target, so only the block hash decides
+    ext.execute_with(|| CurrentTarget::<Runtime>::put(Target::MAX));
+    ext
+}
+
+/// Finishes the current block and returns its hash.
+fn finish_block() -> H256 {
+    let number = System::block_number();
+    Timestamp::set_timestamp(u64::from(number) * 12_000);
+    BasicPallet::on_finalize(number);
+    System::finalize().hash()
+}
+
+/// Imports empty blocks up to `number`, so `frame_system` knows their hashes.
+fn run_to_block(number: u32) {
+    while System::block_number() < number {
+        let parent_hash = match System::block_number() {
+            0 => System::block_hash(0),
+            _ => finish_block(),
+        };
+        System::initialize(
+            &(System::block_number() + 1),
+            &parent_hash,
+            &Digest::default(),
+        );
+    }
+}
+
+fn submit(block_hash: H256) -> DispatchResult {
+    BasicPallet::submit_block(RuntimeOrigin::signed(MINER), vec![0], block_hash)
+}
+
+#[test]
+fn shares_against_recent_blocks_are_accepted() {
+    new_test_ext().execute_with(|| {
+        run_to_block(5);
+        // The genesis block and the parent
+        assert_ok!(submit(System::block_hash(0)));
+        assert_ok!(submit(System::parent_hash()));
+    });
+}
+
+#[test]
+fn shares_against_blocks_outside_the_window_are_rejected() {
+    new_test_ext().execute_with(|| {
+        let window: u32 = RecentBlockWindow::get();
+        run_to_block(window + 2);
+
+        let oldest = System::block_hash(2);
+        assert_ok!(submit(oldest));
+        assert_noop!(
+            submit(System::block_hash(1)),
+            Error::<Runtime>::StaleBlockHash
+        );
+    });
+}
+
+#[test]
+fn shares_against_unknown_blocks_are_rejected() {
+    new_test_ext().execute_with(|| {
+        run_to_block(3);
+        assert_noop!(
+            submit(H256::repeat_byte(0xab)),
+            Error::<Runtime>::StaleBlockHash
+        );
+        // Blocks not yet imported read as the default hash
+        assert_noop!(submit(H256::zero()), Error::<Runtime>::StaleBlockHash);
+    });
+}
+
+#[test]
+fn one_stale_share_fails_the_batch() {
+    new_test_ext().execute_with(|| {
+        run_to_block(3);
+        let shares = vec![
+            (vec![0], System::parent_hash()),
+            (vec![1], H256::repeat_byte(0xab)),
+        ];
+        assert_noop!(
+            BasicPallet::submit_blocks(RuntimeOrigin::signed(MINER), shares),
+            Error::<Runtime>::StaleBlockHash
+        );
+    });
+}
+
+#[test]
+fn window_fits_the_kept_block_hashes() {
+    new_test_ext().execute_with(BasicPallet::integrity_test);
+}
+
+#[test]
+fn replayed_work_is_rejected() {
+    new_test_ext().execute_with(|| {
+        run_to_block(3);
+        let anchor = System::parent_hash();
+        assert_ok!(submit(anchor));
+        assert_noop!(submit(anchor), Error::<Runtime>::DuplicateWork);
+
+        // Nor does it become new in a later block
+        run_to_block(4);
+        assert_noop!(submit(anchor), Error::<Runtime>::DuplicateWork);
+
+        // The same nonce from another miner is other work
+        assert_ok!(BasicPallet::submit_block(
+            RuntimeOrigin::signed(AccountId::new([8; 32])),
+            vec![0],
+            anchor
+        ));
+    });
+}
+
+#[test]
+fn duplicates_within_a_batch_are_rejected() {
+    new_test_ext().execute_with(|| {
+        run_to_block(3);
+        let anchor = System::parent_hash();
+        let shares = vec![(vec![0], anchor), (vec![1], anchor), (vec![0], anchor)];
+        assert_noop!(
+            BasicPallet::submit_blocks(RuntimeOrigin::signed(MINER), shares),
+            Error::<Runtime>::DuplicateWork
+        );
+        // Nothing of the failed batch was recorded
+        assert_ok!(submit(anchor));
+    });
+}
+
+#[test]
+fn shares_per_block_are_bounded() {
+    new_test_ext().execute_with(|| {
+        run_to_block(3);
+        let anchor = System::parent_hash();
+        let max: u32 = MaxSharesPerBlock::get();
+        let batch: u32 = MaxBlockClaims::get();
+        let shares: Vec<_> = (0..max)
+            .map(|nonce| (nonce.to_le_bytes().to_vec(), anchor))
+            .collect();
+        for chunk in shares.chunks(batch as usize) {
+            assert_ok!(BasicPallet::submit_blocks(
+                RuntimeOrigin::signed(MINER),
+                chunk.to_vec()
+            ));
+        }
+        assert_eq!(SolvedWorkCount::<Runtime>::get(2), max);
+
+        assert_noop!(
+            BasicPallet::submit_block(
+                RuntimeOrigin::signed(MINER),
+                max.to_le_bytes().to_vec(),
+                anchor
+            ),
+            Error::<Runtime>::TooManyShares
+        );
+        // Other blocks have room of their own
+        assert_ok!(submit(System::block_hash(1)));
+    });
+}
+
+#[test]
+fn work_is_forgotten_once_its_block_leaves_the_window() {
+    new_test_ext().execute_with(|| {
+        let window: u32 = RecentBlockWindow::get();
+        run_to_block(3);
+        let anchor = System::parent_hash();
+        let work = BasicPallet::verify_proof_of_work(&MINER, &[0], anchor).unwrap();
+        assert_ok!(submit(anchor));
+
+        // Block 2 is still the oldest a share may name
+        run_to_block(window + 2);
+        assert!(SolvedWork::<Runtime>::contains_key(2, work));
+        assert_noop!(submit(anchor), Error::<Runtime>::DuplicateWork);
+
+        run_to_block(window + 3);
+        assert!(!SolvedWork::<Runtime>::contains_key(2, work));
+        assert_eq!(SolvedWorkCount::<Runtime>::get(2), 0);
+        assert_noop!(submit(anchor), Error::<Runtime>::StaleBlockHash);
+    });
+}
@@ -0,0 +1,41 @@
+//! Transaction signatures are ML-DSA-65, checked against the account they claim.
+
+use ml_dsa::{ExpandedSigningKey, MlDsa65, Seed};
+use runtime::{PQPublic, PQSignature, PUBLIC_KEY_LEN, SIGNATURE_LEN};
+use sp_runtime::traits::{IdentifyAccount, Verify};
+
+fn sign(seed: u8, msg: &[u8]) -> (PQPublic, PQSignature) {
+    let key = ExpandedSigningKey::<MlDsa65>::from_seed(&Seed::from([seed; 32]));
+    let public: [u8; PUBLIC_KEY_LEN] = key.verifying_key().encode().into();
+    let signature: [u8; SIGNATURE_LEN] = key.sign_deterministic(msg, &[]).unwrap().encode().into();
+    let public = PQPublic(public);
+    let signature = PQSignature::MlDsa65 {
+        public: public.clone(),
+        signature,
+    };
+    (public, signature)
+}
+
+#[test]
+fn signature_verifies_for_its_key_account() {
+    let (public, signature) = sign(1, b"transfer");
+    assert!(signature.verify(&b"transfer"[..], &public.into_account()));
+}
+
+#[test]
+fn signature_fails_for_another_message_or_account() {
+    let (public, signature) = sign(1, b"transfer");
+    let (other, _) = sign(2, b"transfer");
+    assert!(!signature.verify(&b"transfer!"[..], &public.into_account()));
+    assert!(!signature.verify(&b"transfer"[..], &other.into_account()));
+}
+
+#[test]
+fn key_that_does_not_decode_fails() {
+    let (public, _) = sign(1, b"transfer");
+    let signature = PQSignature::MlDsa65 {
+        public: public.clone(),
+        signature: [0xff; SIGNATURE_LEN],
+    };
+    assert!(!signature.verify(&b"transfer"[..], &public.into_account()));
+}
@@ -0,0 +1,107 @@
+//! The mining target: the full-hash share check, genesis validation and the
+//! migration from the `u64` difficulty.
+
+mod common;
+
+use common::{new_test_ext, MINER};
+use frame_support::{
+    storage::unhashed,
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+};
+use pallet_basic_pallet::{migrations::DifficultyToTarget, CurrentTarget};
+use parity_scale_codec::Encode;
+use pow_primitives::target::{Target, U256};
+use runtime::{BasicPallet, Runtime, RuntimeGenesisConfig};
+use sp_core::{hashing::twox_128, H256};
+use sp_runtime::{traits::Hash, BuildStorage};
+
+/// Hash the share check computes for `nonce` against `block_hash`.
+fn share_hash(nonce: &[u8], block_hash: H256) -> U256 {
+    let mut data = MINER.encode();
+    data.extend_from_slice(nonce);
+    data.extend_from_slice(&block_hash.encode());
+    let hash = <Runtime as frame_system::Config>::Hashing::hash(&data);
+    U256::from_big_endian(hash.as_bytes())
+}
+
+/// Storage key of the version 0 difficulty.
+fn legacy_difficulty_key() -> Vec<u8> {
+    [twox_128(b"BasicPallet"), twox_128(b"CurrentDifficulty")].concat()
+}
+
+#[test]
+fn share_check_compares_the_whole_hash() {
+    new_test_ext().execute_with(|| {
+        let block_hash = H256::repeat_byte(1);
+        let hash = share_hash(&[0], block_hash);
+
+        CurrentTarget::<Runtime>::put(Target::new(hash).unwrap());
+        assert!(BasicPallet::verify_proof_of_work(&MINER, &[0], block_hash).is_ok());
+
+        // One below differs only in the low bytes, which a 64-bit check never saw
+        CurrentTarget::<Runtime>::put(Target::new(hash - 1).unwrap());
+        assert!(BasicPallet::verify_proof_of_work(&MINER, &[0], block_hash).is_err());
+    });
+}
+
+#[test]
+fn genesis_stores_the_target_of_the_initial_difficulty() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(
+            BasicPallet::current_target(),
+            Target::from_difficulty(1_000_000).unwrap()
+        );
+        assert_eq!(
+            BasicPallet::on_chain_storage_version(),
+            BasicPallet::current_storage_version()
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "The initial difficulty must not be zero")]
+fn genesis_rejects_zero_difficulty() {
+    let config = RuntimeGenesisConfig {
+        basic_pallet: pallet_basic_pallet::GenesisConfig {
+            initial_difficulty: 0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let _ = config.build_storage();
+}
+
+#[test]
+fn u64_difficulty_is_migrated_to_a_target() {
+    new_test_ext().execute_with(|| {
+        // State as left by storage version 0
+        CurrentTarget::<Runtime>::kill();
+        unhashed::put(&legacy_difficulty_key(), &1_234_567u64);
+        StorageVersion::new(0).put::<BasicPallet>();
+
+        DifficultyToTarget::<Runtime>::on_runtime_upgrade();
+
+        assert_eq!(BasicPallet::current_difficulty(), 1_234_567);
+        assert_eq!(BasicPallet::target(), U256::MAX / 1_234_567);
+        assert_eq!(unhashed::get_raw(&legacy_difficulty_key()), None);
+        assert_eq!(BasicPallet::on_chain_storage_version(), 1);
+
+        // Later upgrades leave the target alone
+        CurrentTarget::<Runtime>::put(Target::MAX);
+        DifficultyToTarget::<Runtime>::on_runtime_upgrade();
+        assert_eq!(BasicPallet::current_target(), Target::MAX);
+    });
+}
+
+#[test]
+fn zero_u64_difficulty_migrates_to_the_easiest_target() {
+    new_test_ext().execute_with(|| {
+        unhashed::put(&legacy_difficulty_key(), &0u64);
+        StorageVersion::new(0).put::<BasicPallet>();
+
+        DifficultyToTarget::<Runtime>::on_runtime_upgrade();
+
+        assert_eq!(BasicPallet::current_target(), Target::MAX);
+        assert_eq!(BasicPallet::current_difficulty(), 1);
+    });
+}
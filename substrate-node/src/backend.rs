@@ -0,0 +1,66 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::simd::Midstate;
+
+/// How each mining thread computes hashes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Backend {
+    /// One nonce at a time through the `blake3` crate.
+    #[default]
+    Scalar,
+    /// `simd::LANES` nonces at a time from a precomputed midstate. Falls back to
+    /// `Scalar` for work longer than one BLAKE3 chunk.
+    Simd,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 2] = [Backend::Scalar, Backend::Simd];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Scalar => "scalar",
+            Backend::Simd => "simd",
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Backend::ALL
+            .into_iter()
+            .find(|backend| backend.name() == s)
+            .ok_or_else(|| format!("unknown backend `{s}` (expected scalar or simd)"))
+    }
+}
+
+/// A backend prepared for one piece of work.
+pub(crate) enum Kernel<'a> {
+    Scalar(&'a [u8]),
+    Simd(Midstate),
+}
+
+impl<'a> Kernel<'a> {
+    pub(crate) fn new(backend: Backend, work: &'a [u8]) -> Self {
+        match backend {
+            Backend::Simd => Midstate::new(work).map_or(Kernel::Scalar(work), Kernel::Simd),
+            Backend::Scalar => Kernel::Scalar(work),
+        }
+    }
+
+    /// First nonce in `start..start + count` that meets `target`.
+    pub(crate) fn find_first(&self, target: u32, start: u64, count: u64) -> Option<u64> {
+        match self {
+            Kernel::Scalar(work) => crate::mine_batch(work, target, start, count),
+            Kernel::Simd(midstate) => midstate.find_first(target, start, count),
+        }
+    }
+}
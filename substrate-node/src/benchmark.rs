@@ -0,0 +1,274 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::backend::{Backend, Kernel};
+use crate::BURST_SIZE;
+
+/// Nonces scanned by the self-test, which compares every backend's solutions in
+/// this window with the `blake3` reference to catch missed solutions.
+pub const SELF_TEST_NONCES: u64 = 1 << 18;
+
+/// What `benchmark` measures.
+#[derive(Clone, Debug)]
+pub struct BenchmarkConfig {
+    /// Time spent on each backend.
+    pub duration: Duration,
+    /// Threads for the multi-threaded run; no such run is made for 1.
+    pub threads: usize,
+    pub work: Vec<u8>,
+    /// Kept easy so that enough solutions turn up to be checked.
+    pub target: u32,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_secs(1),
+            threads: default_threads(),
+            work: b"pow-kernel benchmark".to_vec(),
+            target: u32::MAX >> 16,
+        }
+    }
+}
+
+/// Hashrate and correctness of one backend.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BackendReport {
+    pub backend: &'static str,
+    pub threads: usize,
+    pub hashes: u64,
+    pub elapsed_secs: f64,
+    pub hashrate: f64,
+    /// Solutions found during the timed run.
+    pub solutions: u64,
+    /// Timed-run solutions that the `blake3` reference rejects.
+    pub invalid_solutions: u64,
+    /// Reference solutions in the self-test window that the backend did not report.
+    pub missed_solutions: u64,
+    /// Wrong answers (invalid plus missed) per solution checked, counting both the
+    /// timed-run solutions and the self-test window.
+    pub error_rate: f64,
+}
+
+/// A backend `benchmark` did not run, and why.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SkippedBackend {
+    pub backend: &'static str,
+    pub reason: &'static str,
+}
+
+/// Results of `benchmark` for every backend compiled into this build.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BenchmarkReport {
+    pub target: u32,
+    pub backends: Vec<BackendReport>,
+    /// Backends that were not measured, so that a missing one is never mistaken
+    /// for a passing one.
+    pub skipped: Vec<SkippedBackend>,
+}
+
+impl BenchmarkReport {
+    /// Whether every backend produced only correct answers.
+    pub fn passed(&self) -> bool {
+        self.backends
+            .iter()
+            .all(|report| report.invalid_solutions == 0 && report.missed_solutions == 0)
+    }
+}
+
+/// Runs each backend for `config.duration`, checking its solutions against the
+/// `blake3` reference, and reports hashes per second and error rates.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn benchmark(config: &BenchmarkConfig) -> BenchmarkReport {
+    use std::time::Instant;
+
+    let reference = reference_solutions(&config.work, config.target);
+    let mut backends: Vec<_> = Backend::ALL
+        .into_iter()
+        .map(|backend| {
+            let started = Instant::now();
+            run_single(backend, config, &reference, || started.elapsed())
+        })
+        .collect();
+    if config.threads > 1 {
+        backends.push(run_threaded(Backend::Simd, config, &reference));
+    }
+    BenchmarkReport {
+        target: config.target,
+        backends,
+        skipped: skipped_backends(),
+    }
+}
+
+/// Backends this build cannot run. `shader.wgsl` has no host-side driver yet, so
+/// there is no GPU backend.
+pub(crate) fn skipped_backends() -> Vec<SkippedBackend> {
+    vec![SkippedBackend {
+        backend: "gpu",
+        reason: "unavailable: no GPU backend in this build",
+    }]
+}
+
+/// Single-threaded run of `backend`, timed by `elapsed` so it also works where
+/// `std::time::Instant` is unavailable (WASM).
+pub(crate) fn run_single(
+    backend: Backend,
+    config: &BenchmarkConfig,
+    reference: &[u64],
+    elapsed: impl Fn() -> Duration,
+) -> BackendReport {
+    let kernel = Kernel::new(backend, &config.work);
+    let mut tally = Tally::default();
+    let mut nonce = 0u64;
+    while elapsed() < config.duration {
+        match kernel.find_first(config.target, nonce, BURST_SIZE) {
+            Some(found) => {
+                tally.hashes += found - nonce + 1;
+                tally.check(config, found);
+                nonce = found + 1;
+            }
+            None => {
+                tally.hashes += BURST_SIZE;
+                nonce += BURST_SIZE;
+            }
+        }
+    }
+    let elapsed = elapsed();
+
+    tally.self_test(reference, |range| {
+        kernel.find_first(config.target, range.start, range.end - range.start)
+    });
+    tally.finish(backend, 1, elapsed)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_threaded(backend: Backend, config: &BenchmarkConfig, reference: &[u64]) -> BackendReport {
+    use crate::{MinerHandle, SearchConfig};
+    use std::time::Instant;
+
+    let miner = MinerHandle::new();
+    let search = |nonces: Range<u64>, deadline: Option<Duration>| {
+        let search = SearchConfig {
+            backend,
+            threads: config.threads,
+            nonces,
+            deadline,
+        };
+        miner.search(&config.work, config.target, &search)
+    };
+
+    let mut tally = Tally::default();
+    let started = Instant::now();
+    let mut nonces = 0..u64::MAX;
+    while let Some(remaining) = config.duration.checked_sub(started.elapsed()) {
+        let outcome = search(nonces.clone(), Some(remaining));
+        tally.hashes += outcome.hashes;
+        let Some(found) = outcome.solution else {
+            break; // Deadline
+        };
+        tally.check(config, found);
+        nonces.start = found + 1;
+    }
+    let elapsed = started.elapsed();
+
+    tally.self_test(reference, |range| search(range, None).solution);
+    tally.finish(backend, config.threads, elapsed)
+}
+
+/// Solutions in the self-test window according to the `blake3` reference.
+pub(crate) fn reference_solutions(work: &[u8], target: u32) -> Vec<u64> {
+    (0..SELF_TEST_NONCES)
+        .filter(|&nonce| reference_meets_target(work, nonce, target))
+        .collect()
+}
+
+/// Hashes `work || nonce` in one call to `blake3::hash`, independently of the
+/// incremental hashing and midstates the backends use.
+fn reference_meets_target(work: &[u8], nonce: u64, target: u32) -> bool {
+    let input = [work, &nonce.to_le_bytes()].concat();
+    let hash = blake3::hash(&input);
+    let bytes = hash.as_bytes();
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) <= target
+}
+
+/// Walks the self-test window with `next_solution` and counts reference solutions
+/// it skipped over.
+fn count_missed(
+    reference: &[u64],
+    mut next_solution: impl FnMut(Range<u64>) -> Option<u64>,
+) -> u64 {
+    let mut found = Vec::with_capacity(reference.len());
+    let mut range = 0..SELF_TEST_NONCES;
+    while let Some(nonce) = next_solution(range.clone()) {
+        found.push(nonce);
+        range.start = nonce + 1;
+    }
+    reference
+        .iter()
+        .filter(|nonce| found.binary_search(nonce).is_err())
+        .count() as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn default_threads() -> usize {
+    1
+}
+
+#[derive(Default)]
+struct Tally {
+    hashes: u64,
+    solutions: u64,
+    invalid: u64,
+    self_tested: u64,
+    missed: u64,
+}
+
+impl Tally {
+    fn check(&mut self, config: &BenchmarkConfig, nonce: u64) {
+        self.solutions += 1;
+        if !reference_meets_target(&config.work, nonce, config.target) {
+            self.invalid += 1;
+        }
+    }
+
+    fn self_test(
+        &mut self,
+        reference: &[u64],
+        next_solution: impl FnMut(Range<u64>) -> Option<u64>,
+    ) {
+        self.self_tested = reference.len() as u64;
+        self.missed = count_missed(reference, next_solution);
+    }
+
+    fn finish(self, backend: Backend, threads: usize, elapsed: Duration) -> BackendReport {
+        let elapsed_secs = elapsed.as_secs_f64();
+        let checked = self.solutions + self.self_tested;
+        BackendReport {
+            backend: backend.name(),
+            threads,
+            hashes: self.hashes,
+            elapsed_secs,
+            hashrate: if elapsed_secs > 0.0 {
+                self.hashes as f64 / elapsed_secs
+            } else {
+                0.0
+            },
+            solutions: self.solutions,
+            invalid_solutions: self.invalid,
+            missed_solutions: self.missed,
+            error_rate: if checked > 0 {
+                (self.invalid + self.missed) as f64 / checked as f64
+            } else {
+                0.0
+            },
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//! `pow-miner`: mine or verify BLAKE3 proof-of-work from the command line.
+//!
+//!     pow-miner mine --work 68656c6c6f --difficulty 1000 --threads 4 --backend simd
+//!     pow-miner verify --work 68656c6c6f --difficulty 1000 --nonce 1234
+//!     pow-miner bench --duration 2 --format json
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use pow_kernel::{Backend, BenchmarkConfig, MinerHandle, SearchConfig};
+use serde_json::json;
+
+#[derive(Parser)]
+#[command(name = "pow-miner", version, about = "BLAKE3 proof-of-work miner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Search for nonces that meet the target
+    Mine(MineArgs),
+    /// Check whether a nonce meets the target
+    Verify(VerifyArgs),
+    /// Measure the hashrate of every backend and check its solutions
+    Bench(BenchArgs),
+}
+
+#[derive(Args)]
+struct WorkArgs {
+    /// Work to mine, as hex
+    #[arg(
+        long,
+        conflicts_with = "work_file",
+        required_unless_present = "work_file"
+    )]
+    work: Option<String>,
+    /// Read the work as raw bytes from a file
+    #[arg(long)]
+    work_file: Option<PathBuf>,
+    /// Target for the first 4 hash bytes (u32 big-endian), decimal or 0x-hex
+    #[arg(
+        long,
+        conflicts_with = "difficulty",
+        required_unless_present = "difficulty"
+    )]
+    target: Option<String>,
+    /// Difficulty, converted to a target of u32::MAX / difficulty
+    #[arg(long)]
+    difficulty: Option<u32>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Args)]
+struct MineArgs {
+    #[command(flatten)]
+    work: WorkArgs,
+    /// Mining threads (defaults to the number of CPUs)
+    #[arg(long)]
+    threads: Option<usize>,
+    /// First nonce to try
+    #[arg(long, default_value_t = 0)]
+    start: u64,
+    /// Stop before this nonce
+    #[arg(long, default_value_t = u64::MAX)]
+    end: u64,
+    /// Hashing backend: scalar or simd
+    #[arg(long, default_value_t = Backend::Simd)]
+    backend: Backend,
+    /// Give up after this many seconds
+    #[arg(long)]
+    deadline: Option<f64>,
+    /// Number of solutions to find
+    #[arg(long, default_value_t = 1)]
+    solutions: usize,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    #[command(flatten)]
+    work: WorkArgs,
+    /// Nonce to check
+    #[arg(long)]
+    nonce: u64,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Seconds to run each backend
+    #[arg(long, default_value_t = 1.0)]
+    duration: f64,
+    /// Threads for the multi-threaded run (defaults to the number of CPUs)
+    #[arg(long)]
+    threads: Option<usize>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Mine(args) => mine(args),
+        Command::Verify(args) => verify(args),
+        Command::Bench(args) => bench(args),
+    };
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn mine(args: MineArgs) -> Result<ExitCode, String> {
+    let work = args.work.load()?;
+    let target = args.work.target()?;
+    if args.start >= args.end {
+        return Err("--start must be below --end".into());
+    }
+    let threads = match args.threads {
+        Some(threads) => threads.max(1),
+        None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+    let deadline = args
+        .deadline
+        .map(Duration::try_from_secs_f64)
+        .transpose()
+        .map_err(|err| format!("invalid --deadline: {err}"))?;
+
+    let miner = MinerHandle::new();
+    let started = Instant::now();
+    let mut solutions = Vec::new();
+    let mut hashes = 0;
+    let mut nonces = args.start..args.end;
+    while solutions.len() < args.solutions && !nonces.is_empty() {
+        let remaining = match deadline {
+            Some(deadline) => match deadline.checked_sub(started.elapsed()) {
+                Some(remaining) => Some(remaining),
+                None => break,
+            },
+            None => None,
+        };
+        let config = SearchConfig {
+            backend: args.backend,
+            threads,
+            nonces: nonces.clone(),
+            deadline: remaining,
+        };
+        let outcome = miner.search(&work, target, &config);
+        hashes += outcome.hashes;
+        let Some(nonce) = outcome.solution else {
+            break; // Deadline or range exhausted
+        };
+        if args.work.format == Format::Text {
+            println!(
+                "solution nonce={nonce} hash={}",
+                hex::encode(pow_kernel::pow_hash(&work, nonce))
+            );
+        }
+        solutions.push(nonce);
+        nonces.start = nonce.saturating_add(1);
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    let hashrate = if elapsed > 0.0 {
+        hashes as f64 / elapsed
+    } else {
+        0.0
+    };
+    match args.work.format {
+        Format::Text => {
+            println!(
+                "{} of {} solutions, {hashes} hashes in {elapsed:.3}s ({}) [{} x {threads}]",
+                solutions.len(),
+                args.solutions,
+                format_hashrate(hashrate),
+                args.backend,
+            );
+        }
+        Format::Json => {
+            let solutions: Vec<_> = solutions
+                .iter()
+                .map(|&nonce| json!({ "nonce": nonce, "hash": hex::encode(pow_kernel::pow_hash(&work, nonce)) }))
+                .collect();
+            let report = json!({
+                "backend": args.backend.name(),
+                "threads": threads,
+                "target": target,
+                "solutions": solutions,
+                "hashes": hashes,
+                "elapsed_secs": elapsed,
+                "hashrate": hashrate,
+            });
+            println!("{report}");
+        }
+    }
+    Ok(if solutions.len() == args.solutions {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+fn verify(args: VerifyArgs) -> Result<ExitCode, String> {
+    let work = args.work.load()?;
+    let target = args.work.target()?;
+    let hash = pow_kernel::pow_hash(&work, args.nonce);
+    let valid = pow_kernel::meets_target(&work, args.nonce, target);
+    match args.work.format {
+        Format::Text => {
+            let verdict = if valid { "valid" } else { "invalid" };
+            println!(
+                "{verdict} nonce={} hash={} target={target}",
+                args.nonce,
+                hex::encode(hash)
+            );
+        }
+        Format::Json => {
+            let report = json!({
+                "nonce": args.nonce,
+                "hash": hex::encode(hash),
+                "target": target,
+                "valid": valid,
+            });
+            println!("{report}");
+        }
+    }
+    Ok(if valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+fn bench(args: BenchArgs) -> Result<ExitCode, String> {
+    let mut config = BenchmarkConfig {
+        duration: Duration::try_from_secs_f64(args.duration)
+            .map_err(|err| format!("invalid --duration: {err}"))?,
+        ..BenchmarkConfig::default()
+    };
+    if let Some(threads) = args.threads {
+        config.threads = threads.max(1);
+    }
+
+    let report = pow_kernel::benchmark(&config);
+    match args.format {
+        Format::Text => {
+            for backend in &report.backends {
+                println!(
+                    "{:>6} x {:<3} {:>12}  {} solutions, {} invalid, {} missed (error rate {:.4})",
+                    backend.backend,
+                    backend.threads,
+                    format_hashrate(backend.hashrate),
+                    backend.solutions,
+                    backend.invalid_solutions,
+                    backend.missed_solutions,
+                    backend.error_rate,
+                );
+            }
+            for skipped in &report.skipped {
+                println!("{:>6} skipped: {}", skipped.backend, skipped.reason);
+            }
+            let verdict = if report.passed() { "passed" } else { "FAILED" };
+            println!("self-test {verdict}");
+        }
+        Format::Json => {
+            let report = serde_json::to_string(&report).map_err(|err| err.to_string())?;
+            println!("{report}");
+        }
+    }
+    Ok(if report.passed() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    })
+}
+
+impl WorkArgs {
+    fn load(&self) -> Result<Vec<u8>, String> {
+        match (&self.work, &self.work_file) {
+            (Some(work), _) => {
+                let work = work.strip_prefix("0x").unwrap_or(work);
+                hex::decode(work).map_err(|err| format!("invalid --work hex: {err}"))
+            }
+            (None, Some(path)) => {
+                std::fs::read(path).map_err(|err| format!("reading {}: {err}", path.display()))
+            }
+            (None, None) => Err("one of --work or --work-file is required".into()),
+        }
+    }
+
+    fn target(&self) -> Result<u32, String> {
+        match (&self.target, self.difficulty) {
+            (Some(target), _) => {
+                let parsed = match target.strip_prefix("0x") {
+                    Some(hex) => u32::from_str_radix(hex, 16),
+                    None => target.parse(),
+                };
+                parsed.map_err(|err| format!("invalid --target: {err}"))
+            }
+            (None, Some(0)) => Err("--difficulty must be at least 1".into()),
+            (None, Some(difficulty)) => Ok(u32::MAX / difficulty),
+            (None, None) => Err("one of --target or --difficulty is required".into()),
+        }
+    }
+}
+
+fn format_hashrate(hashrate: f64) -> String {
+    match hashrate {
+        h if h >= 1e9 => format!("{:.2} GH/s", h / 1e9),
+        h if h >= 1e6 => format!("{:.2} MH/s", h / 1e6),
+        h if h >= 1e3 => format!("{:.2} kH/s", h / 1e3),
+        h => format!("{h:.0} H/s"),
+    }
+}
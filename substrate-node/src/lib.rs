@@ -1,43 +1,65 @@
-// Removed wasm-bindgen related imports
-use std::sync::atomic::{AtomicBool, Ordering};
-// Removed unused bytemuck import
+mod backend;
+mod benchmark;
+mod miner;
+pub mod simd;
+mod throttle;
 
-static ABORT_FLAG: AtomicBool = AtomicBool::new(false);
+pub use backend::Backend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use benchmark::benchmark;
+pub use benchmark::{
+    BackendReport, BenchmarkConfig, BenchmarkReport, SkippedBackend, SELF_TEST_NONCES,
+};
+pub use miner::{handle, MinerHandle, SearchConfig, SearchOutcome, BURST_SIZE};
+pub use throttle::Throttle;
 
-/// Searches for a nonce such that the BLAKE3 hash of `work` concatenated with `nonce`
-/// (represented as little-endian bytes) is less than or equal to `target`.
+// JavaScript bindings (wasm-bindgen), enabled with the `wasm` feature
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Searches for a nonce such that the prefix of the BLAKE3 hash of `work` concatenated
+/// with `nonce` (represented as little-endian bytes) is less than or equal to `target`.
 /// Returns `Some(nonce)` if found, `None` if aborted via `stop()`.
+/// Runs on the global miner, so `handle().set_throttle(..)` applies to it.
+#[cfg(not(target_arch = "wasm32"))]
 pub fn mine(work: &[u8], target: u32) -> Option<u64> {
-    ABORT_FLAG.store(false, Ordering::SeqCst);
-
-    // CPU mining loop
-    {
-        let work = work.to_vec(); // Clone work data for the loop
-        let mut nonce: u64 = 0;
-        loop { // Changed from `while` to `loop` for explicit break/return
-            if ABORT_FLAG.load(Ordering::SeqCst) {
-                return None; // Aborted
-            }
-            let mut hasher = blake3::Hasher::new();
-            hasher.update(&work);
-            hasher.update(&nonce.to_le_bytes());
-            let hash = hasher.finalize();
-            let bytes = hash.as_bytes();
-            // Compare the first 4 bytes (as u32 little-endian) against the target
-            let h0 = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-            if h0 <= target {
-                return Some(nonce); // Found
-            }
-            nonce = nonce.wrapping_add(1);
-            // Potential yield point for cooperative multitasking if needed in WASM?
-            // For now, it's a blocking loop.
-        }
-    }
+    handle().mine(work, target)
+}
+
+/// Checks `count` consecutive nonces starting at `start` and returns the first
+/// one that meets `target`. Does not look at the abort flag, so callers that
+/// need to stay responsive (e.g. the WASM bindings) mine in bounded batches.
+pub fn mine_batch(work: &[u8], target: u32, start: u64, count: u64) -> Option<u64> {
+    (0..count)
+        .map(|i| start.wrapping_add(i))
+        .find(|&nonce| meets_target(work, nonce, target))
+}
+
+/// BLAKE3 hash of `work` concatenated with the little-endian bytes of `nonce`.
+pub fn pow_hash(work: &[u8], nonce: u64) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(work);
+    hasher.update(&nonce.to_le_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+/// The first 4 bytes of the PoW hash as a big-endian u32, the value compared against
+/// the target. These are the top 32 bits of the hash read as a big-endian 256-bit
+/// number, so a hash can only meet a 256-bit target if its prefix meets the target's
+/// top 32 bits.
+pub fn hash_prefix(work: &[u8], nonce: u64) -> u32 {
+    let bytes = pow_hash(work, nonce);
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// Returns whether `nonce` solves `work` for `target`.
+pub fn meets_target(work: &[u8], nonce: u64, target: u32) -> bool {
+    hash_prefix(work, nonce) <= target
 }
 
 /// Signal cancellation to mining.
 pub fn stop() {
-    ABORT_FLAG.store(true, Ordering::SeqCst);
+    handle().stop();
 }
 
 /// Compute standard BLAKE3 hash (32 bytes) of input.
@@ -46,4 +68,4 @@ pub fn blake3_hash(input: &[u8]) -> Vec<u8> {
     hasher.update(input);
     let hash = hasher.finalize();
     hash.as_bytes().to_vec()
-}
\ No newline at end of file
+}
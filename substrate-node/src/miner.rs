@@ -0,0 +1,200 @@
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::backend::{Backend, Kernel};
+use crate::throttle::{AtomicThrottle, Throttle};
+
+/// Nonces hashed per burst before checking for `stop()` and applying the throttle.
+pub const BURST_SIZE: u64 = 4_096;
+
+struct MinerState {
+    abort: AtomicBool,
+    throttle: AtomicThrottle,
+}
+
+/// Shared control over a miner. Clones refer to the same miner, so one thread can
+/// mine while another stops it or changes its throttle at runtime.
+#[derive(Clone)]
+pub struct MinerHandle {
+    state: Arc<MinerState>,
+}
+
+impl Default for MinerHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MinerHandle {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(MinerState {
+                abort: AtomicBool::new(false),
+                throttle: AtomicThrottle::new(),
+            }),
+        }
+    }
+
+    /// Signal cancellation to mining.
+    pub fn stop(&self) {
+        self.state.abort.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether `stop()` has been called since mining last started.
+    pub fn is_stopped(&self) -> bool {
+        self.state.abort.load(Ordering::SeqCst)
+    }
+
+    /// Clears the abort flag before a new mining run.
+    pub fn reset(&self) {
+        self.state.abort.store(false, Ordering::SeqCst);
+    }
+
+    /// Change the throttle; a running miner applies it from its next burst.
+    pub fn set_throttle(&self, throttle: Throttle) {
+        self.state.throttle.store(throttle);
+    }
+
+    pub fn throttle(&self) -> Throttle {
+        self.state.throttle.load()
+    }
+
+    /// Searches for a nonce meeting `target`, hashing in bursts and idling between
+    /// them as the throttle requires. Returns `None` if aborted via `stop()`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn mine(&self, work: &[u8], target: u32) -> Option<u64> {
+        self.search(work, target, &SearchConfig::default()).solution
+    }
+
+    /// Searches `config.nonces` for the lowest nonce meeting `target`, splitting the
+    /// range into bursts shared out between `config.threads` threads. Ends on a
+    /// solution, `stop()`, the deadline, or when the range is exhausted.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn search(&self, work: &[u8], target: u32, config: &SearchConfig) -> SearchOutcome {
+        use std::sync::atomic::AtomicU64;
+        use std::time::Instant;
+
+        self.reset();
+        let started = Instant::now();
+        let deadline = config.deadline.map(|deadline| started + deadline);
+        let threads = config.threads.max(1);
+        let kernel = Kernel::new(config.backend, work);
+        let next = AtomicU64::new(config.nonces.start);
+        let best = AtomicU64::new(u64::MAX);
+        let hashes = AtomicU64::new(0);
+        // One past the highest nonce hashed
+        let tried = AtomicU64::new(config.nonces.start);
+
+        let worker = || loop {
+            if self.is_stopped() || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return;
+            }
+            let throttle = self.throttle().per_thread(threads);
+            let count = throttle.burst_size(BURST_SIZE);
+            // Bursts are claimed in increasing order, so once a solution is known
+            // only earlier bursts can still improve on it
+            let start = next
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                    Some(n.saturating_add(count))
+                })
+                .unwrap_or_else(|n| n);
+            if start >= config.nonces.end || start >= best.load(Ordering::SeqCst) {
+                return;
+            }
+            let count = count.min(config.nonces.end - start);
+
+            let burst_started = Instant::now();
+            let found = kernel.find_first(target, start, count);
+            let end = found.map_or(start + count, |nonce| nonce + 1);
+            hashes.fetch_add(end - start, Ordering::Relaxed);
+            tried.fetch_max(end, Ordering::SeqCst);
+            if let Some(nonce) = found {
+                best.fetch_min(nonce, Ordering::SeqCst);
+                return;
+            }
+            self.idle(throttle.pause_after(count, burst_started.elapsed()));
+        };
+        std::thread::scope(|scope| {
+            for _ in 1..threads {
+                scope.spawn(worker);
+            }
+            worker();
+        });
+
+        let best = best.load(Ordering::SeqCst);
+        let tried = tried.load(Ordering::SeqCst);
+        SearchOutcome {
+            solution: (best != u64::MAX).then_some(best),
+            hashes: hashes.load(Ordering::Relaxed),
+            last_nonce: (tried > config.nonces.start).then(|| tried - 1),
+            elapsed: started.elapsed(),
+        }
+    }
+
+    /// Sleeps for `pause` in short slices, returning early on `stop()`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn idle(&self, mut pause: Duration) {
+        while !pause.is_zero() && !self.is_stopped() {
+            let slice = pause.min(crate::throttle::MAX_PAUSE);
+            std::thread::sleep(slice);
+            pause -= slice;
+        }
+    }
+}
+
+/// Parameters of a native `MinerHandle::search`.
+#[derive(Clone, Debug)]
+pub struct SearchConfig {
+    pub backend: Backend,
+    /// Number of mining threads (at least 1).
+    pub threads: usize,
+    /// Nonces to search, in increasing order.
+    pub nonces: Range<u64>,
+    /// Give up after this long.
+    pub deadline: Option<Duration>,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            backend: Backend::Scalar,
+            threads: 1,
+            nonces: 0..u64::MAX,
+            deadline: None,
+        }
+    }
+}
+
+/// Result of a `MinerHandle::search`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchOutcome {
+    /// The lowest solving nonce in the searched range, if one was found.
+    pub solution: Option<u64>,
+    /// Nonces hashed across all threads.
+    pub hashes: u64,
+    /// Highest nonce hashed. Without a solution, every nonce from the start of the
+    /// range up to it was hashed, so a search can resume after it.
+    pub last_nonce: Option<u64>,
+    pub elapsed: Duration,
+}
+
+impl SearchOutcome {
+    /// Hashes per second over the whole search.
+    pub fn hashrate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.hashes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The process-wide miner driven by the free `mine()`/`stop()` functions and the
+/// WASM bindings.
+pub fn handle() -> MinerHandle {
+    static GLOBAL: OnceLock<MinerHandle> = OnceLock::new();
+    GLOBAL.get_or_init(MinerHandle::new).clone()
+}
@@ -78,9 +78,10 @@
          G(&v, 3u, 4u, 9u, 14u, m[s[14]], m[s[15]]);
      }
 
-     // Finalize and check target
+     // Finalize and check target against the big-endian prefix
      let h0 = v[0] ^ v[8] ^ IV[0];
-     if (h0 <= params.target) {
+     let prefix = (h0 << 24u) | ((h0 << 8u) & 0x00ff0000u) | ((h0 >> 8u) & 0x0000ff00u) | (h0 >> 24u);
+     if (prefix <= params.target) {
          let prev = atomicCompareExchangeWeak(&result.found, 0u, 1u).old_value;
          if (prev == 0u) {
              result.nonce = nonce;
@@ -0,0 +1,237 @@
+//! Lane-parallel BLAKE3 for nonce search.
+//!
+//! The input `work || nonce` is hashed for `LANES` nonces at once. Every 64-byte block
+//! made only of `work` bytes is compressed once up front (the midstate), so each
+//! nonce only pays for the final one or two blocks. The lane loops are written over
+//! plain arrays so the compiler can map them onto SSE/AVX/NEON/WASM SIMD registers.
+//! Only single-chunk inputs (up to 1024 bytes) are supported.
+
+/// Nonces hashed together.
+pub const LANES: usize = 8;
+
+type Lanes = [u32; LANES];
+
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+const MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const BLOCK_LEN: usize = 64;
+const CHUNK_LEN: usize = 1024;
+const CHUNK_START: u32 = 1;
+const CHUNK_END: u32 = 2;
+const ROOT: u32 = 8;
+
+/// Precomputed BLAKE3 state for hashing `work || nonce` over many nonces.
+pub struct Midstate {
+    /// Chaining value after the leading blocks that contain no nonce bytes
+    cv: [u32; 8],
+    /// Whether any block was compressed into `cv` (otherwise the tail starts the chunk)
+    started: bool,
+    /// Bytes of `work` after the compressed blocks; the nonce follows them
+    tail: Vec<u8>,
+    /// Message words of the final block(s) with the nonce bytes left as zero
+    blocks: [[u32; 16]; 2],
+}
+
+impl Midstate {
+    /// Returns `None` if `work || nonce` does not fit in a single BLAKE3 chunk.
+    pub fn new(work: &[u8]) -> Option<Self> {
+        let total = work.len() + 8;
+        if total > CHUNK_LEN {
+            return None;
+        }
+        // Keep at least the final block (which carries the nonce) for per-nonce hashing
+        let blocks = total.div_ceil(BLOCK_LEN);
+        let prefix_blocks = (work.len() / BLOCK_LEN).min(blocks - 1);
+
+        let mut cv = IV;
+        for (i, block) in work[..prefix_blocks * BLOCK_LEN]
+            .chunks_exact(BLOCK_LEN)
+            .enumerate()
+        {
+            let flags = if i == 0 { CHUNK_START } else { 0 };
+            let words = block_words(block);
+            let out = compress(&cv.map(splat), &words.map(splat), BLOCK_LEN as u32, flags);
+            cv = out.map(|lane| lane[0]);
+        }
+
+        let tail = work[prefix_blocks * BLOCK_LEN..].to_vec();
+        let mut padded = [0u8; 2 * BLOCK_LEN];
+        padded[..tail.len()].copy_from_slice(&tail);
+        let blocks = [
+            block_words(&padded[..BLOCK_LEN]),
+            block_words(&padded[BLOCK_LEN..]),
+        ];
+
+        Some(Self {
+            cv,
+            started: prefix_blocks > 0,
+            tail,
+            blocks,
+        })
+    }
+
+    /// First nonce in `start..start + count` whose hash prefix is `<= target`.
+    pub fn find_first(&self, target: u32, start: u64, count: u64) -> Option<u64> {
+        // The default x86_64 target only guarantees SSE2, which lacks cheap lane
+        // rotations, so pick the AVX2 build at runtime where the CPU has it
+        #[cfg(target_arch = "x86_64")]
+        if std::is_x86_feature_detected!("avx2") {
+            // SAFETY: AVX2 support was just checked
+            return unsafe { self.find_first_avx2(target, start, count) };
+        }
+        self.find_first_lanes(target, start, count)
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx2")]
+    unsafe fn find_first_avx2(&self, target: u32, start: u64, count: u64) -> Option<u64> {
+        self.find_first_lanes(target, start, count)
+    }
+
+    #[inline(always)]
+    fn find_first_lanes(&self, target: u32, start: u64, count: u64) -> Option<u64> {
+        let mut offset = 0;
+        while offset < count {
+            let base = start.wrapping_add(offset);
+            let prefixes = self.hash_lanes(base)[0];
+            let live = (count - offset).min(LANES as u64) as usize;
+            // Output words are little-endian, the prefix is big-endian
+            if let Some(lane) = prefixes[..live]
+                .iter()
+                .position(|&h0| h0.swap_bytes() <= target)
+            {
+                return Some(base.wrapping_add(lane as u64));
+            }
+            offset += LANES as u64;
+        }
+        None
+    }
+
+    /// Full 32-byte BLAKE3 hash of `work || nonce`.
+    pub fn hash(&self, nonce: u64) -> [u8; 32] {
+        let words = self.hash_lanes(nonce);
+        let mut out = [0u8; 32];
+        for (bytes, word) in out.chunks_exact_mut(4).zip(words) {
+            bytes.copy_from_slice(&word[0].to_le_bytes());
+        }
+        out
+    }
+
+    /// Output words of the root hash for nonces `base..base + LANES`.
+    #[inline(always)]
+    fn hash_lanes(&self, base: u64) -> [Lanes; 8] {
+        let len = self.tail.len() + 8;
+        let blocks = len.div_ceil(BLOCK_LEN);
+        let mut cv = self.cv.map(splat);
+        for block in 0..blocks {
+            let mut words = self.blocks[block].map(splat);
+            // Splice the nonce bytes that fall into this block into each lane
+            for (i, position) in (self.tail.len()..len).enumerate() {
+                let Some(offset) = position
+                    .checked_sub(block * BLOCK_LEN)
+                    .filter(|&o| o < BLOCK_LEN)
+                else {
+                    continue;
+                };
+                let shift = 8 * (offset % 4) as u32;
+                let word = &mut words[offset / 4];
+                for (lane, value) in word.iter_mut().enumerate() {
+                    let nonce = base.wrapping_add(lane as u64);
+                    *value |= ((nonce >> (8 * i)) as u8 as u32) << shift;
+                }
+            }
+
+            let mut flags = 0;
+            if block == 0 && !self.started {
+                flags |= CHUNK_START;
+            }
+            let block_len = if block + 1 == blocks {
+                flags |= CHUNK_END | ROOT;
+                len - block * BLOCK_LEN
+            } else {
+                BLOCK_LEN
+            };
+            cv = compress(&cv, &words, block_len as u32, flags);
+        }
+        cv
+    }
+}
+
+fn splat(word: u32) -> Lanes {
+    [word; LANES]
+}
+
+fn block_words(block: &[u8]) -> [u32; 16] {
+    let mut words = [0u32; 16];
+    for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    }
+    words
+}
+
+#[inline(always)]
+fn add(a: Lanes, b: Lanes) -> Lanes {
+    let mut out = a;
+    for (o, b) in out.iter_mut().zip(b) {
+        *o = o.wrapping_add(b);
+    }
+    out
+}
+
+#[inline(always)]
+fn xor_rotr(a: Lanes, b: Lanes, bits: u32) -> Lanes {
+    let mut out = a;
+    for (o, b) in out.iter_mut().zip(b) {
+        *o = (*o ^ b).rotate_right(bits);
+    }
+    out
+}
+
+#[inline(always)]
+fn g(v: &mut [Lanes; 16], a: usize, b: usize, c: usize, d: usize, x: Lanes, y: Lanes) {
+    v[a] = add(add(v[a], v[b]), x);
+    v[d] = xor_rotr(v[d], v[a], 16);
+    v[c] = add(v[c], v[d]);
+    v[b] = xor_rotr(v[b], v[c], 12);
+    v[a] = add(add(v[a], v[b]), y);
+    v[d] = xor_rotr(v[d], v[a], 8);
+    v[c] = add(v[c], v[d]);
+    v[b] = xor_rotr(v[b], v[c], 7);
+}
+
+/// BLAKE3 compression of one block per lane (chunk counter 0), returning the
+/// chaining value, which for a `ROOT` block is also the first 32 bytes of output.
+#[inline(always)]
+fn compress(cv: &[Lanes; 8], words: &[Lanes; 16], block_len: u32, flags: u32) -> [Lanes; 8] {
+    #[rustfmt::skip]
+    let mut v = [
+        cv[0], cv[1], cv[2], cv[3], cv[4], cv[5], cv[6], cv[7],
+        splat(IV[0]), splat(IV[1]), splat(IV[2]), splat(IV[3]),
+        splat(0), splat(0), splat(block_len), splat(flags),
+    ];
+    let mut m = *words;
+    for round in 0..7 {
+        // Mix the columns
+        g(&mut v, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut v, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut v, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut v, 3, 7, 11, 15, m[6], m[7]);
+        // Mix the diagonals
+        g(&mut v, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut v, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut v, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut v, 3, 4, 9, 14, m[14], m[15]);
+        if round < 6 {
+            m = MSG_PERMUTATION.map(|i| m[i]);
+        }
+    }
+    let mut out = [[0u32; LANES]; 8];
+    for (i, word) in out.iter_mut().enumerate() {
+        for lane in 0..LANES {
+            word[lane] = v[i][lane] ^ v[i + 8][lane];
+        }
+    }
+    out
+}
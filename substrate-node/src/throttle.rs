@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Limits how hard the miner works, so a background tab or laptop isn't pinned at
+/// 100% CPU. The miner hashes in short bursts and idles between them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Throttle {
+    /// Mine flat out.
+    #[default]
+    Off,
+    /// Spend at most this percentage (1-100) of wall-clock time hashing.
+    DutyCycle(u8),
+    /// Stay at or below this many hashes per second.
+    Hashrate(u64),
+}
+
+/// Longest single idle period; longer pauses are split so `stop()` and throttle
+/// changes are picked up promptly.
+pub(crate) const MAX_PAUSE: Duration = Duration::from_millis(50);
+
+impl Throttle {
+    /// How long to idle after a burst of `hashes` that took `busy` to compute.
+    pub fn pause_after(&self, hashes: u64, busy: Duration) -> Duration {
+        match *self {
+            Throttle::Off => Duration::ZERO,
+            Throttle::DutyCycle(percent) => {
+                let percent = percent.clamp(1, 100) as u32;
+                busy * (100 - percent) / percent
+            }
+            Throttle::Hashrate(rate) => {
+                let budget = Duration::from_secs_f64(hashes as f64 / rate.max(1) as f64);
+                budget.saturating_sub(busy)
+            }
+        }
+    }
+
+    /// The share of this throttle each of `threads` threads should apply, so the
+    /// limit holds for the miner as a whole.
+    pub fn per_thread(&self, threads: usize) -> Throttle {
+        match *self {
+            Throttle::Hashrate(rate) => Throttle::Hashrate((rate / threads.max(1) as u64).max(1)),
+            throttle => throttle,
+        }
+    }
+
+    /// Number of hashes per burst, at most `max`. A low hashrate cap gets
+    /// proportionally smaller bursts so the idle periods stay short.
+    pub fn burst_size(&self, max: u64) -> u64 {
+        match *self {
+            Throttle::Hashrate(rate) => (rate / 20).clamp(1, max),
+            _ => max,
+        }
+    }
+}
+
+// Packed as a tag in the top byte and the value in the low 56 bits
+const TAG_SHIFT: u32 = 56;
+const VALUE_MASK: u64 = (1 << TAG_SHIFT) - 1;
+const TAG_DUTY_CYCLE: u64 = 1;
+const TAG_HASHRATE: u64 = 2;
+
+/// A `Throttle` that can be swapped while a miner is running.
+pub(crate) struct AtomicThrottle(AtomicU64);
+
+impl AtomicThrottle {
+    pub(crate) const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    pub(crate) fn load(&self) -> Throttle {
+        let packed = self.0.load(Ordering::Relaxed);
+        let value = packed & VALUE_MASK;
+        match packed >> TAG_SHIFT {
+            TAG_DUTY_CYCLE => Throttle::DutyCycle(value as u8),
+            TAG_HASHRATE => Throttle::Hashrate(value),
+            _ => Throttle::Off,
+        }
+    }
+
+    pub(crate) fn store(&self, throttle: Throttle) {
+        let packed = match throttle {
+            Throttle::Off => 0,
+            Throttle::DutyCycle(percent) => (TAG_DUTY_CYCLE << TAG_SHIFT) | percent as u64,
+            Throttle::Hashrate(rate) => (TAG_HASHRATE << TAG_SHIFT) | rate.min(VALUE_MASK),
+        };
+        self.0.store(packed, Ordering::Relaxed);
+    }
+}
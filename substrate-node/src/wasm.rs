@@ -0,0 +1,174 @@
+//! wasm-bindgen bindings exposing the PoW kernel to JavaScript.
+//!
+//! Built with `wasm-pack build -- --features wasm`; `app/NodeContext.tsx` imports
+//! `init`, `startMining` and `stop` from the generated package.
+
+use std::time::Duration;
+
+use js_sys::{Date, Function, Object, Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::benchmark::{reference_solutions, run_single, skipped_backends};
+use crate::throttle::MAX_PAUSE;
+use crate::{Backend, BenchmarkConfig, BenchmarkReport, MinerHandle, Throttle};
+
+// Multi-worker mining over a SharedArrayBuffer, enabled with the `wasm-threads` feature
+#[cfg(feature = "wasm-threads")]
+pub mod threads;
+
+/// Nonces hashed between yields back to the JS event loop. Small enough that
+/// `stop()` and UI events are handled promptly, large enough to amortize the yield.
+const BATCH_SIZE: u64 = 16_384;
+
+#[wasm_bindgen]
+extern "C" {
+    // Available as a global in both browsers and Node
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &Function, timeout: i32) -> JsValue;
+}
+
+/// Prepare the miner. Resolves once mining can start.
+#[wasm_bindgen]
+pub async fn init() -> Result<(), JsValue> {
+    // Route Rust panics to console.error instead of an opaque `unreachable`
+    console_error_panic_hook::set_once();
+    Ok(())
+}
+
+/// Mine `work` until a nonce meeting `target` is found or `stop()` is called.
+///
+/// `on_solution(nonce)` is called with the winning nonce, and the returned Promise
+/// resolves to the same nonce (or `undefined` if mining was stopped).
+/// `on_progress({ hashes, nonce, elapsedMs, hashrate })` is called after every batch.
+#[wasm_bindgen(js_name = startMining)]
+pub async fn start_mining(
+    work: Vec<u8>,
+    target: u32,
+    on_solution: Function,
+    on_progress: Option<Function>,
+) -> Result<JsValue, JsValue> {
+    let miner = crate::handle();
+    miner.reset();
+    let started = Date::now();
+    let mut nonce: u64 = 0;
+    let mut hashes: u64 = 0;
+
+    loop {
+        if miner.is_stopped() {
+            return Ok(JsValue::UNDEFINED); // Aborted
+        }
+        let throttle = miner.throttle();
+        let count = throttle.burst_size(BATCH_SIZE);
+        let batch_started = Date::now();
+        if let Some(found) = crate::mine_batch(&work, target, nonce, count) {
+            let found = JsValue::from_f64(found as f64);
+            on_solution.call1(&JsValue::NULL, &found)?;
+            return Ok(found);
+        }
+        nonce = nonce.wrapping_add(count);
+        hashes += count;
+
+        if let Some(on_progress) = &on_progress {
+            let report = progress_report(hashes, nonce, Date::now() - started)?;
+            on_progress.call1(&JsValue::NULL, &report)?;
+        }
+        // Let the event loop run so `stop()` and other callbacks get a chance,
+        // staying idle for as long as the throttle asks
+        let busy = Duration::from_secs_f64((Date::now() - batch_started).max(0.0) / 1000.0);
+        idle(&miner, throttle.pause_after(count, busy)).await?;
+    }
+}
+
+/// Signal cancellation to a running `startMining` call.
+#[wasm_bindgen]
+pub fn stop() {
+    crate::stop();
+}
+
+/// Limit mining to `percent` (1-100) of wall-clock time. Applies to a running
+/// `startMining` call from its next batch.
+#[wasm_bindgen(js_name = setDutyCycle)]
+pub fn set_duty_cycle(percent: u8) {
+    crate::handle().set_throttle(Throttle::DutyCycle(percent));
+}
+
+/// Cap mining at `hashes_per_second`.
+#[wasm_bindgen(js_name = setHashrateLimit)]
+pub fn set_hashrate_limit(hashes_per_second: f64) {
+    crate::handle().set_throttle(Throttle::Hashrate(hashes_per_second as u64));
+}
+
+/// Remove any duty-cycle or hashrate limit.
+#[wasm_bindgen(js_name = clearThrottle)]
+pub fn clear_throttle() {
+    crate::handle().set_throttle(Throttle::Off);
+}
+
+/// Run every single-threaded backend for `duration_ms` each and return the
+/// `BenchmarkReport` (hashrates plus error rates against the `blake3` reference).
+/// Blocks while it runs, so call it from a worker in the browser.
+#[wasm_bindgen]
+pub fn benchmark(duration_ms: f64) -> Result<JsValue, JsValue> {
+    let config = BenchmarkConfig {
+        duration: Duration::from_secs_f64(duration_ms.max(0.0) / 1000.0),
+        ..BenchmarkConfig::default()
+    };
+    let reference = reference_solutions(&config.work, config.target);
+    let backends = Backend::ALL
+        .into_iter()
+        .map(|backend| {
+            let started = Date::now();
+            let elapsed = || Duration::from_secs_f64((Date::now() - started).max(0.0) / 1000.0);
+            run_single(backend, &config, &reference, elapsed)
+        })
+        .collect();
+    let report = BenchmarkReport {
+        target: config.target,
+        backends,
+        skipped: skipped_backends(),
+    };
+    Ok(serde_wasm_bindgen::to_value(&report)?)
+}
+
+/// Standard BLAKE3 hash (32 bytes) of `input`, for checking parity with JS hashers.
+#[wasm_bindgen(js_name = blake3Hash)]
+pub fn blake3_hash(input: &[u8]) -> Vec<u8> {
+    crate::blake3_hash(input)
+}
+
+fn progress_report(hashes: u64, nonce: u64, elapsed_ms: f64) -> Result<JsValue, JsValue> {
+    let hashrate = if elapsed_ms > 0.0 {
+        hashes as f64 * 1000.0 / elapsed_ms
+    } else {
+        0.0
+    };
+    let report = Object::new();
+    Reflect::set(&report, &"hashes".into(), &JsValue::from_f64(hashes as f64))?;
+    Reflect::set(&report, &"nonce".into(), &JsValue::from_f64(nonce as f64))?;
+    Reflect::set(&report, &"elapsedMs".into(), &JsValue::from_f64(elapsed_ms))?;
+    Reflect::set(&report, &"hashrate".into(), &JsValue::from_f64(hashrate))?;
+    Ok(report.into())
+}
+
+/// Yields for `pause` in short slices, returning early on `stop()`. Always yields at
+/// least once.
+async fn idle(miner: &MinerHandle, mut pause: Duration) -> Result<(), JsValue> {
+    loop {
+        let slice = pause.min(MAX_PAUSE);
+        sleep(slice).await?;
+        pause -= slice;
+        if pause.is_zero() || miner.is_stopped() {
+            return Ok(());
+        }
+    }
+}
+
+/// Resolves after `pause` (on the next macrotask at the least), giving queued JS
+/// events a chance to run.
+async fn sleep(pause: Duration) -> Result<(), JsValue> {
+    let promise = Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, pause.as_millis() as i32);
+    });
+    JsFuture::from(promise).await.map(|_| ())
+}
@@ -0,0 +1,193 @@
+//! Parallel mining across Web Workers.
+//!
+//! Every worker runs its own instance of the module and searches a disjoint nonce
+//! range with `mineRange`. The abort flag and the best solution found so far live in
+//! a `SharedArrayBuffer` that the main thread hands to each worker, so they are
+//! visible to every worker and to the main thread, which can `Atomics.wait` (or
+//! `Atomics.waitAsync`) on the `SEQ` slot to be woken when a solution is published.
+//!
+//! Built with `npm run build:threads` (atomics + shared memory, nightly `build-std`).
+
+use js_sys::{Atomics, Int32Array, Object, Reflect, SharedArrayBuffer};
+use wasm_bindgen::prelude::*;
+
+use super::BATCH_SIZE;
+
+#[cfg(all(target_arch = "wasm32", not(target_feature = "atomics")))]
+compile_error!(
+    "the `wasm-threads` feature needs `-C target-feature=+atomics,+bulk-memory` (see `npm run build:threads`)"
+);
+
+// Layout of the shared state, in i32 slots
+/// Non-zero once mining should stop
+const ABORT: u32 = 0;
+/// Spin lock guarding the solution slots
+const LOCK: u32 = 1;
+/// Bumped (and notified) every time a solution is published
+const SEQ: u32 = 2;
+/// Non-zero once a solution has been published
+const HAS_SOLUTION: u32 = 3;
+const NONCE_LO: u32 = 4;
+const NONCE_HI: u32 = 5;
+/// Hash prefix (u32 bit pattern) of the published solution
+const HASH_PREFIX: u32 = 6;
+const SLOTS: u32 = 7;
+
+/// View over the `SharedArrayBuffer` shared by all workers of one mining job.
+#[wasm_bindgen]
+pub struct SharedMiningState {
+    view: Int32Array,
+}
+
+#[wasm_bindgen]
+impl SharedMiningState {
+    /// Wrap `buffer`, which must be at least `SharedMiningState.byteLength()` bytes.
+    #[wasm_bindgen(constructor)]
+    pub fn new(buffer: &SharedArrayBuffer) -> Result<SharedMiningState, JsValue> {
+        if buffer.byte_length() < Self::byte_length() {
+            return Err(JsError::new("shared mining buffer is too small").into());
+        }
+        let view = Int32Array::new_with_byte_offset_and_length(buffer, 0, SLOTS);
+        Ok(Self { view })
+    }
+
+    /// Size of the shared state in bytes.
+    #[wasm_bindgen(js_name = byteLength)]
+    pub fn byte_length() -> u32 {
+        SLOTS * 4
+    }
+
+    /// Index of the slot that is bumped and notified on every published solution.
+    #[wasm_bindgen(js_name = seqIndex)]
+    pub fn seq_index() -> u32 {
+        SEQ
+    }
+
+    /// Clear the abort flag and the solution slot before a new job.
+    pub fn reset(&self) -> Result<(), JsValue> {
+        for slot in [ABORT, HAS_SOLUTION, NONCE_LO, NONCE_HI, HASH_PREFIX] {
+            Atomics::store(&self.view, slot, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Signal every worker of this job to stop.
+    pub fn abort(&self) -> Result<(), JsValue> {
+        Atomics::store(&self.view, ABORT, 1)?;
+        // Wake anyone waiting for a solution so they can observe the abort
+        Atomics::notify(&self.view, SEQ)?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = isAborted)]
+    pub fn is_aborted(&self) -> Result<bool, JsValue> {
+        Ok(Atomics::load(&self.view, ABORT)? != 0)
+    }
+
+    /// The best solution published so far as `{ nonce, hashPrefix }`, or `null`.
+    pub fn solution(&self) -> Result<JsValue, JsValue> {
+        self.lock()?;
+        let solution = self.read_solution();
+        self.unlock()?;
+        match solution? {
+            Some((nonce, hash_prefix)) => {
+                let solution = Object::new();
+                Reflect::set(&solution, &"nonce".into(), &JsValue::from_f64(nonce as f64))?;
+                Reflect::set(
+                    &solution,
+                    &"hashPrefix".into(),
+                    &JsValue::from_f64(hash_prefix as f64),
+                )?;
+                Ok(solution.into())
+            }
+            None => Ok(JsValue::NULL),
+        }
+    }
+}
+
+impl SharedMiningState {
+    /// Record a solution if it beats the current one, then stop the other workers.
+    fn publish(&self, nonce: u64, hash_prefix: u32) -> Result<(), JsValue> {
+        self.lock()?;
+        let result = self.store_if_better(nonce, hash_prefix);
+        self.unlock()?;
+        result?;
+
+        Atomics::add(&self.view, SEQ, 1)?;
+        self.abort()
+    }
+
+    fn store_if_better(&self, nonce: u64, hash_prefix: u32) -> Result<(), JsValue> {
+        if let Some((_, best)) = self.read_solution()? {
+            if best <= hash_prefix {
+                return Ok(());
+            }
+        }
+        Atomics::store(&self.view, NONCE_LO, nonce as u32 as i32)?;
+        Atomics::store(&self.view, NONCE_HI, (nonce >> 32) as u32 as i32)?;
+        Atomics::store(&self.view, HASH_PREFIX, hash_prefix as i32)?;
+        Atomics::store(&self.view, HAS_SOLUTION, 1)?;
+        Ok(())
+    }
+
+    /// Reads the solution slots; callers hold the lock.
+    fn read_solution(&self) -> Result<Option<(u64, u32)>, JsValue> {
+        if Atomics::load(&self.view, HAS_SOLUTION)? == 0 {
+            return Ok(None);
+        }
+        let lo = Atomics::load(&self.view, NONCE_LO)? as u32 as u64;
+        let hi = Atomics::load(&self.view, NONCE_HI)? as u32 as u64;
+        let hash_prefix = Atomics::load(&self.view, HASH_PREFIX)? as u32;
+        Ok(Some(((hi << 32) | lo, hash_prefix)))
+    }
+
+    fn lock(&self) -> Result<(), JsValue> {
+        // Held only for a handful of loads and stores, so spinning is fine
+        while Atomics::compare_exchange(&self.view, LOCK, 0, 1)? != 0 {
+            std::hint::spin_loop();
+        }
+        Ok(())
+    }
+
+    fn unlock(&self) -> Result<(), JsValue> {
+        Atomics::store(&self.view, LOCK, 0)?;
+        Ok(())
+    }
+}
+
+/// Search nonces in `start..end` until a solution is found or the job is aborted.
+///
+/// Meant to be called from a worker, where blocking is fine. Returns `true` if this
+/// worker published a solution to `state`.
+#[wasm_bindgen(js_name = mineRange)]
+pub fn mine_range(
+    work: &[u8],
+    target: u32,
+    start: f64,
+    end: f64,
+    state: &SharedMiningState,
+) -> Result<bool, JsValue> {
+    let (mut nonce, end) = (start as u64, end as u64);
+    while nonce < end {
+        if state.is_aborted()? {
+            return Ok(false); // Aborted, by the main thread or a faster worker
+        }
+        let count = BATCH_SIZE.min(end - nonce);
+        if let Some(found) = crate::mine_batch(work, target, nonce, count) {
+            state.publish(found, crate::hash_prefix(work, found))?;
+            return Ok(true);
+        }
+        nonce += count;
+    }
+    Ok(false)
+}
+
+/// Split the nonce space into `workers` disjoint ranges and return `[start, end)` of
+/// the range for worker `index`. Nonces stay below 2^53 so they are exact JS numbers.
+#[wasm_bindgen(js_name = nonceRange)]
+pub fn nonce_range(index: u32, workers: u32) -> Vec<f64> {
+    const NONCE_SPACE: u64 = 1 << 53;
+    let span = NONCE_SPACE / workers.max(1) as u64;
+    let start = span * index as u64;
+    vec![start as f64, (start + span) as f64]
+}
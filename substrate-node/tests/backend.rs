@@ -0,0 +1,100 @@
+use pow_kernel::simd::{Midstate, LANES};
+use std::time::Duration;
+
+use pow_kernel::{Backend, BenchmarkConfig, MinerHandle, SearchConfig};
+
+#[test]
+fn simd_hash_matches_blake3_for_every_block_layout() {
+    // Cover empty work, partial and full blocks, the nonce straddling a block
+    // boundary, and the largest single-chunk input
+    for len in (0..=140).chain([1000, 1015, 1016]) {
+        let work: Vec<u8> = (0..len).map(|i| (i * 7 + 3) as u8).collect();
+        let midstate = Midstate::new(&work).expect("single chunk");
+        for nonce in [0, 1, 255, 1 << 32, u64::MAX] {
+            assert_eq!(
+                midstate.hash(nonce),
+                pow_kernel::pow_hash(&work, nonce),
+                "len {len}, nonce {nonce}"
+            );
+        }
+    }
+    assert!(Midstate::new(&[0u8; 1017]).is_none());
+}
+
+#[test]
+fn hash_prefix_is_the_top_of_the_big_endian_hash() {
+    let hash = pow_kernel::pow_hash(b"prefix", 3);
+    assert_eq!(
+        pow_kernel::hash_prefix(b"prefix", 3).to_be_bytes(),
+        hash[..4]
+    );
+}
+
+#[test]
+fn simd_and_scalar_find_the_same_nonces() {
+    let work = b"backend parity";
+    let target = u32::MAX / 16;
+    let scalar = pow_kernel::mine_batch(work, target, 10, 1_000);
+    let midstate = Midstate::new(work).unwrap();
+    assert_eq!(midstate.find_first(target, 10, 1_000), scalar);
+
+    // Lanes past the end of the range must not be reported, even when the
+    // solution sits in the same lane group
+    let first = scalar.unwrap();
+    assert_ne!((first - 10) % LANES as u64, 0);
+    assert_eq!(midstate.find_first(target, 10, first - 10), None);
+}
+
+#[test]
+fn threaded_search_finds_lowest_nonce_in_range() {
+    let work = b"threaded search";
+    let target = u32::MAX / 4_096;
+    let expected = pow_kernel::mine_batch(work, target, 1_000, 1_000_000);
+    for backend in Backend::ALL {
+        let config = SearchConfig {
+            backend,
+            threads: 4,
+            nonces: 1_000..1_001_000,
+            deadline: None,
+        };
+        let outcome = MinerHandle::new().search(work, target, &config);
+        assert_eq!(outcome.solution, expected, "{backend}");
+        assert!(outcome.hashes > 0);
+    }
+}
+
+#[test]
+fn search_stops_when_range_is_exhausted() {
+    let config = SearchConfig {
+        nonces: 0..10_000,
+        ..SearchConfig::default()
+    };
+    let outcome = MinerHandle::new().search(b"exhausted", 0, &config);
+    assert_eq!(outcome.solution, None);
+    assert_eq!(outcome.hashes, 10_000);
+    assert_eq!(outcome.last_nonce, Some(9_999));
+}
+
+#[test]
+fn benchmark_reports_every_backend_without_errors() {
+    let config = BenchmarkConfig {
+        duration: Duration::from_millis(50),
+        threads: 2,
+        ..BenchmarkConfig::default()
+    };
+    let report = pow_kernel::benchmark(&config);
+
+    let runs: Vec<_> = report
+        .backends
+        .iter()
+        .map(|b| (b.backend, b.threads))
+        .collect();
+    assert_eq!(runs, [("scalar", 1), ("simd", 1), ("simd", 2)]);
+    for backend in &report.backends {
+        assert!(backend.hashes > 0 && backend.hashrate > 0.0, "{backend:?}");
+        assert_eq!(backend.error_rate, 0.0, "{backend:?}");
+    }
+    let skipped: Vec<_> = report.skipped.iter().map(|s| s.backend).collect();
+    assert_eq!(skipped, ["gpu"]);
+    assert!(report.passed());
+}
@@ -0,0 +1,93 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use pow_kernel::{MinerHandle, SearchConfig, Throttle};
+
+#[test]
+fn duty_cycle_pauses_in_proportion() {
+    let busy = Duration::from_millis(30);
+    assert_eq!(Throttle::Off.pause_after(1_000, busy), Duration::ZERO);
+    assert_eq!(
+        Throttle::DutyCycle(100).pause_after(1_000, busy),
+        Duration::ZERO
+    );
+    assert_eq!(
+        Throttle::DutyCycle(30).pause_after(1_000, busy),
+        Duration::from_millis(70)
+    );
+    assert_eq!(Throttle::DutyCycle(50).pause_after(1_000, busy), busy);
+}
+
+#[test]
+fn hashrate_cap_pauses_for_remaining_budget() {
+    let throttle = Throttle::Hashrate(1_000);
+    assert_eq!(
+        throttle.pause_after(100, Duration::from_millis(40)),
+        Duration::from_millis(60)
+    );
+    assert_eq!(
+        throttle.pause_after(100, Duration::from_millis(200)),
+        Duration::ZERO
+    );
+    assert_eq!(throttle.burst_size(4_096), 50);
+    assert_eq!(Throttle::Hashrate(1).burst_size(4_096), 1);
+}
+
+#[test]
+fn throttle_changes_at_runtime_and_stop_interrupts_idle() {
+    let miner = MinerHandle::new();
+    assert_eq!(miner.throttle(), Throttle::Off);
+
+    // A target of 0 is practically unreachable, so the miner runs until stopped
+    let worker = {
+        let miner = miner.clone();
+        thread::spawn(move || miner.mine(b"throttle test", 0))
+    };
+    miner.set_throttle(Throttle::DutyCycle(1));
+    assert_eq!(miner.throttle(), Throttle::DutyCycle(1));
+    miner.set_throttle(Throttle::Hashrate(10));
+    assert_eq!(miner.throttle(), Throttle::Hashrate(10));
+    thread::sleep(Duration::from_millis(100));
+
+    let stopped_at = Instant::now();
+    miner.stop();
+    assert_eq!(worker.join().unwrap(), None);
+    assert!(stopped_at.elapsed() < Duration::from_millis(500));
+}
+
+/// Nonces `miner` hashes in `window`, at a target of 0 that is practically never met.
+fn hashes_in(miner: &MinerHandle, window: Duration) -> u64 {
+    let config = SearchConfig {
+        deadline: Some(window),
+        ..SearchConfig::default()
+    };
+    miner.search(b"throttle rate", 0, &config).hashes
+}
+
+#[test]
+fn throttle_set_at_runtime_lowers_the_hashrate() {
+    let window = Duration::from_millis(400);
+    let unthrottled = hashes_in(&MinerHandle::new(), window);
+
+    // Throttled to a 10% duty cycle halfway through the window
+    let miner = MinerHandle::new();
+    let throttler = {
+        let miner = miner.clone();
+        thread::spawn(move || {
+            thread::sleep(window / 2);
+            miner.set_throttle(Throttle::DutyCycle(10));
+        })
+    };
+    let throttled = hashes_in(&miner, window);
+    throttler.join().unwrap();
+
+    // About 55% of the unthrottled count; well under 80% even on a noisy machine
+    assert!(
+        throttled * 10 < unthrottled * 8,
+        "{throttled} hashes throttled, {unthrottled} unthrottled"
+    );
+    assert!(
+        throttled * 10 > unthrottled * 3,
+        "{throttled} hashes throttled, {unthrottled} unthrottled"
+    );
+}
@@ -0,0 +1,72 @@
+// Pure-Node harness for the wasm-threads build (`npm run test:threads`).
+// Spawns worker_threads that mine disjoint nonce ranges and coordinate through a
+// SharedArrayBuffer, then checks the published solution and the abort path.
+import assert from 'node:assert/strict';
+import { readFileSync } from 'node:fs';
+import { Worker } from 'node:worker_threads';
+import { initSync, SharedMiningState, blake3Hash } from '../../pkg-threads/pow_kernel.js';
+
+initSync({ module: readFileSync(new URL('../../pkg-threads/pow_kernel_bg.wasm', import.meta.url)) });
+
+const WORKERS = 4;
+const work = new TextEncoder().encode('pow-kernel wasm-threads harness');
+
+function spawn(buffer, target) {
+  return Array.from({ length: WORKERS }, (_, index) =>
+    new Promise((resolve, reject) => {
+      const worker = new Worker(new URL('./worker.mjs', import.meta.url), {
+        workerData: { buffer, work, target, index, workers: WORKERS },
+      });
+      worker.once('message', resolve);
+      worker.once('error', reject);
+    }),
+  );
+}
+
+function hashPrefix(nonce) {
+  const input = new Uint8Array(work.length + 8);
+  input.set(work);
+  new DataView(input.buffer).setBigUint64(work.length, BigInt(nonce), true);
+  const hash = blake3Hash(input);
+  return new DataView(hash.buffer, hash.byteOffset).getUint32(0, false);
+}
+
+async function findsSolution() {
+  const buffer = new SharedArrayBuffer(SharedMiningState.byteLength());
+  const state = new SharedMiningState(buffer);
+  const target = 0xffffffff >>> 12;
+
+  const results = await Promise.all(spawn(buffer, target));
+  const solution = state.solution();
+  assert.ok(solution, 'a solution was published');
+  assert.ok(state.isAborted(), 'publishing a solution stops the other workers');
+  assert.equal(solution.hashPrefix, hashPrefix(solution.nonce), 'hash matches the BLAKE3 reference');
+  assert.ok(solution.hashPrefix <= target, 'solution meets the target');
+
+  const winners = results.filter((r) => r.published);
+  assert.ok(winners.length >= 1);
+  assert.ok(
+    winners.some((r) => solution.nonce >= r.start && solution.nonce < r.end),
+    'solution comes from a publishing worker range',
+  );
+  for (let i = 1; i < results.length; i++) {
+    assert.equal(results[i].start, results[i - 1].end, 'ranges are disjoint and contiguous');
+  }
+}
+
+async function abortStopsAllWorkers() {
+  const buffer = new SharedArrayBuffer(SharedMiningState.byteLength());
+  const state = new SharedMiningState(buffer);
+
+  // A target of 0 is practically unreachable, so only the abort ends the job
+  const running = Promise.all(spawn(buffer, 0));
+  setTimeout(() => state.abort(), 200);
+  const results = await running;
+
+  assert.ok(results.every((r) => !r.published));
+  assert.equal(state.solution(), null);
+}
+
+await findsSolution();
+await abortStopsAllWorkers();
+console.log('wasm-threads harness: ok');
@@ -0,0 +1,13 @@
+// Worker side of the wasm-threads harness: mines one nonce range against the
+// shared state and reports whether it published the solution.
+import { readFileSync } from 'node:fs';
+import { parentPort, workerData } from 'node:worker_threads';
+import { initSync, SharedMiningState, mineRange, nonceRange } from '../../pkg-threads/pow_kernel.js';
+
+initSync({ module: readFileSync(new URL('../../pkg-threads/pow_kernel_bg.wasm', import.meta.url)) });
+
+const { buffer, work, target, index, workers } = workerData;
+const state = new SharedMiningState(buffer);
+const [start, end] = nonceRange(index, workers);
+const published = mineRange(work, target, start, end, state);
+parentPort.postMessage({ index, start, end, published });
@@ -0,0 +1,85 @@
+//! Headless tests for the wasm-bindgen bindings, run on Node with
+//! `wasm-pack test --node -- --features wasm`.
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use js_sys::Function;
+use pow_kernel::wasm;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+const WORK: &[u8] = b"pow-kernel wasm test";
+
+#[wasm_bindgen_test]
+fn blake3_hash_matches_reference() {
+    assert_eq!(
+        wasm::blake3_hash(b"abc"),
+        blake3::hash(b"abc").as_bytes().to_vec()
+    );
+}
+
+#[wasm_bindgen_test]
+async fn start_mining_reports_solution() {
+    wasm::init().await.unwrap();
+
+    let reported = Rc::new(Cell::new(None));
+    let on_solution = {
+        let reported = reported.clone();
+        Closure::<dyn FnMut(f64)>::new(move |nonce: f64| reported.set(Some(nonce)))
+    };
+    let target = u32::MAX / 64;
+    let result = wasm::start_mining(
+        WORK.to_vec(),
+        target,
+        on_solution.as_ref().unchecked_ref::<Function>().clone(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let nonce = result.as_f64().expect("solution nonce");
+    assert_eq!(reported.get(), Some(nonce));
+    assert!(pow_kernel::meets_target(WORK, nonce as u64, target));
+}
+
+#[wasm_bindgen_test]
+async fn stop_cancels_mining() {
+    let batches = Rc::new(Cell::new(0u32));
+    let on_progress = {
+        let batches = batches.clone();
+        Closure::<dyn FnMut(JsValue)>::new(move |_report: JsValue| {
+            batches.set(batches.get() + 1);
+            wasm::stop();
+        })
+    };
+    let on_solution = Closure::<dyn FnMut(f64)>::new(|_nonce: f64| panic!("unexpected solution"));
+
+    // A target of 0 is practically unreachable, so only `stop()` ends the run
+    let result = wasm::start_mining(
+        WORK.to_vec(),
+        0,
+        on_solution.as_ref().unchecked_ref::<Function>().clone(),
+        Some(on_progress.as_ref().unchecked_ref::<Function>().clone()),
+    )
+    .await
+    .unwrap();
+
+    assert!(result.is_undefined());
+    assert_eq!(batches.get(), 1);
+}
+
+#[wasm_bindgen_test]
+fn benchmark_reports_backends() {
+    let report = wasm::benchmark(20.0).unwrap();
+    let backends = js_sys::Reflect::get(&report, &"backends".into()).unwrap();
+    let backends = js_sys::Array::from(&backends);
+    assert_eq!(backends.length(), pow_kernel::Backend::ALL.len() as u32);
+    for backend in backends.iter() {
+        let error_rate = js_sys::Reflect::get(&backend, &"error_rate".into()).unwrap();
+        assert_eq!(error_rate.as_f64(), Some(0.0));
+    }
+    let skipped = js_sys::Reflect::get(&report, &"skipped".into()).unwrap();
+    assert_eq!(js_sys::Array::from(&skipped).length(), 1);
+}
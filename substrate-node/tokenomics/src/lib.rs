@@ -0,0 +1,115 @@
+//! Coin economics shared by the runtime and the TypeScript packages: the block
+//! reward schedule, supply projections and transaction fees.
+//!
+//! Amounts are integers in the smallest unit, so the runtime, native code and the
+//! WebAssembly build in `wasm/` (which `@coin/tokenomics` calls) agree to the unit.
+//! Both sides are checked against the golden vectors in `packages/tokenomics/vectors`.
+
+#![no_std]
+
+/// Fee rates are given in basis points, hundredths of a percent: 1 is 0.01%.
+pub const BASIS_POINTS: u32 = 10_000;
+
+/// One coin in the smallest unit: 8 decimals.
+pub const UNIT: u128 = 100_000_000;
+
+/// Time the difficulty aims to keep between blocks, in milliseconds.
+pub const TARGET_BLOCK_TIME_MS: u64 = 12_000;
+
+/// Fee rate the wallet charges unless told otherwise: 0.01%.
+pub const FEE_BASIS_POINTS: u32 = 1;
+
+/// The schedule the runtime pays block rewards on.
+///
+/// 888,888 coins per block at first, halved every 200,000 blocks (about 28 days at
+/// the target block time). After about 20 halvings one coin per block is left,
+/// and the halvings alone pay out about 355.6 billion coins of the 360 billion cap.
+pub const REWARD_SCHEDULE: RewardSchedule = RewardSchedule {
+    initial_reward: 888_888 * UNIT,
+    halving_interval: 200_000,
+    tail_emission: UNIT,
+    max_supply: 360_000_000_000 * UNIT,
+};
+
+/// How block rewards are paid out.
+///
+/// The block at height `h` earns `initial_reward` shifted right once for every
+/// `halving_interval` blocks in `h`, but never less than `tail_emission`; an
+/// interval of zero never halves. Once `max_supply` has been paid in total, rewards
+/// stop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RewardSchedule {
+    pub initial_reward: u128,
+    pub halving_interval: u64,
+    pub tail_emission: u128,
+    pub max_supply: u128,
+}
+
+impl RewardSchedule {
+    /// Reward of the block at `height`, ignoring the supply cap.
+    pub fn uncapped_reward(&self, height: u64) -> u128 {
+        self.era_reward(height.checked_div(self.halving_interval).unwrap_or(0))
+    }
+
+    /// Reward of the block at `height`, once earlier blocks were paid `minted`.
+    pub fn block_reward(&self, height: u64, minted: u128) -> u128 {
+        self.uncapped_reward(height)
+            .min(self.max_supply.saturating_sub(minted))
+    }
+
+    /// Total paid to the blocks up to and including `height`. The genesis block
+    /// (height 0) is not mined and earns nothing.
+    pub fn supply_at(&self, height: u64) -> u128 {
+        let height = u128::from(height);
+        let interval = u128::from(self.halving_interval);
+        if interval == 0 {
+            return self
+                .initial_reward
+                .max(self.tail_emission)
+                .saturating_mul(height)
+                .min(self.max_supply);
+        }
+
+        let mut supply: u128 = 0;
+        for era in 0u64.. {
+            let start = (u128::from(era) * interval).max(1);
+            if start > height || supply >= self.max_supply {
+                break;
+            }
+            let reward = self.era_reward(era);
+            // From here on every block earns the tail emission
+            let end = if reward == self.tail_emission {
+                height
+            } else {
+                height.min(u128::from(era + 1) * interval - 1)
+            };
+            // Empty when the first era holds only the genesis block
+            if end >= start {
+                supply = supply.saturating_add(reward.saturating_mul(end - start + 1));
+            }
+            if end == height {
+                break;
+            }
+        }
+        supply.min(self.max_supply)
+    }
+
+    /// Reward of the blocks after `halvings` halvings.
+    fn era_reward(&self, halvings: u64) -> u128 {
+        u32::try_from(halvings)
+            .ok()
+            .and_then(|halvings| self.initial_reward.checked_shr(halvings))
+            .unwrap_or(0)
+            .max(self.tail_emission)
+    }
+}
+
+/// Fee on `amount` at `basis_points`, rounded down and saturating.
+pub fn fee(amount: u128, basis_points: u32) -> u128 {
+    let basis_points = u128::from(basis_points);
+    let scale = u128::from(BASIS_POINTS);
+    // amount * bp / scale, split so that no intermediate product overflows
+    (amount / scale)
+        .saturating_mul(basis_points)
+        .saturating_add(amount % scale * basis_points / scale)
+}
@@ -0,0 +1,95 @@
+//! The reward schedule and fees against block-by-block reference models.
+
+use coin_tokenomics::{fee, RewardSchedule, BASIS_POINTS};
+use proptest::prelude::*;
+
+/// Pays blocks 1 to `height` one at a time.
+fn reference_supply(schedule: &RewardSchedule, height: u64) -> u128 {
+    (1..=height).fold(0, |minted, block| {
+        minted + schedule.block_reward(block, minted)
+    })
+}
+
+fn small_schedule() -> impl Strategy<Value = RewardSchedule> {
+    (0u128..5_000, 0u64..50, 0u128..200, 0u128..200_000).prop_map(
+        |(initial_reward, halving_interval, tail_emission, max_supply)| RewardSchedule {
+            initial_reward,
+            halving_interval,
+            tail_emission,
+            max_supply,
+        },
+    )
+}
+
+#[test]
+fn tail_emission_takes_over_from_the_halvings() {
+    let schedule = RewardSchedule {
+        initial_reward: 1_000,
+        halving_interval: 10,
+        tail_emission: 100,
+        max_supply: u128::MAX,
+    };
+    assert_eq!(schedule.block_reward(0, 0), 1_000);
+    assert_eq!(schedule.block_reward(30, 0), 125);
+    assert_eq!(schedule.block_reward(40, 0), 100);
+    assert_eq!(schedule.block_reward(u64::MAX, 0), 100);
+}
+
+#[test]
+fn cap_limits_the_last_rewards() {
+    let schedule = RewardSchedule {
+        initial_reward: 1_000,
+        halving_interval: 10,
+        tail_emission: 100,
+        max_supply: 1_500,
+    };
+    assert_eq!(schedule.block_reward(1, 0), 1_000);
+    assert_eq!(schedule.block_reward(2, 1_000), 500);
+    assert_eq!(schedule.block_reward(3, 1_500), 0);
+    assert_eq!(schedule.supply_at(u64::MAX), 1_500);
+}
+
+#[test]
+fn fee_rates_are_in_basis_points() {
+    assert_eq!(fee(1_000_000, 1), 100);
+    assert_eq!(fee(1_000_000, BASIS_POINTS), 1_000_000);
+    assert_eq!(fee(u128::MAX, 2 * BASIS_POINTS), u128::MAX);
+}
+
+proptest! {
+    #[test]
+    fn supply_matches_paying_each_block(schedule in small_schedule(), height in 0u64..400) {
+        prop_assert_eq!(schedule.supply_at(height), reference_supply(&schedule, height));
+    }
+
+    #[test]
+    fn rewards_never_grow_with_height(
+        a in any::<u64>(),
+        b in any::<u64>(),
+        initial_reward in any::<u128>(),
+        halving_interval in 1u64..,
+        tail_emission in any::<u128>(),
+    ) {
+        prop_assume!(a <= b);
+        let schedule = RewardSchedule { initial_reward, halving_interval, tail_emission, max_supply: u128::MAX };
+        prop_assert!(schedule.uncapped_reward(a) >= schedule.uncapped_reward(b));
+    }
+
+    #[test]
+    fn supply_never_exceeds_the_cap(
+        initial_reward in any::<u128>(),
+        halving_interval in any::<u64>(),
+        tail_emission in any::<u128>(),
+        max_supply in any::<u128>(),
+        height in any::<u64>(),
+    ) {
+        let schedule = RewardSchedule { initial_reward, halving_interval, tail_emission, max_supply };
+        prop_assert!(schedule.supply_at(height) <= max_supply);
+    }
+
+    #[test]
+    fn fee_matches_wide_arithmetic(amount in any::<u64>(), basis_points in 0..=BASIS_POINTS) {
+        let expected = u128::from(amount) * u128::from(basis_points) / u128::from(BASIS_POINTS);
+        prop_assert_eq!(fee(u128::from(amount), basis_points), expected);
+    }
+}
@@ -0,0 +1,107 @@
+//! The golden vectors shared with `@coin/tokenomics`, which runs the same ones
+//! through the WebAssembly build.
+
+use coin_tokenomics::{
+    fee, RewardSchedule, FEE_BASIS_POINTS, REWARD_SCHEDULE, TARGET_BLOCK_TIME_MS, UNIT,
+};
+use serde_json::Value;
+
+const REWARDS: &str = include_str!("../../../packages/tokenomics/vectors/rewards.json");
+const FEES: &str = include_str!("../../../packages/tokenomics/vectors/fees.json");
+const DEFAULTS: &str = include_str!("../../../packages/tokenomics/vectors/defaults.json");
+
+fn number<T: std::str::FromStr>(value: &Value) -> T
+where
+    T::Err: std::fmt::Debug,
+{
+    value.as_str().unwrap().parse().unwrap()
+}
+
+fn schedule(case: &Value) -> RewardSchedule {
+    RewardSchedule {
+        initial_reward: number(&case["initialReward"]),
+        halving_interval: number(&case["halvingInterval"]),
+        tail_emission: number(&case["tailEmission"]),
+        max_supply: number(&case["maxSupply"]),
+    }
+}
+
+fn schedules() -> Vec<(String, RewardSchedule, Value)> {
+    let vectors: Value = serde_json::from_str(REWARDS).unwrap();
+    vectors["schedules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|case| {
+            let schedule = schedule(case);
+            let name = case["name"].as_str().unwrap().to_owned();
+            (name, schedule, case.clone())
+        })
+        .collect()
+}
+
+#[test]
+fn block_rewards() {
+    for (name, schedule, case) in schedules() {
+        for vector in case["blockRewards"].as_array().unwrap() {
+            let height = number(&vector["height"]);
+            let minted = number(&vector["minted"]);
+            assert_eq!(
+                schedule.block_reward(height, minted),
+                number::<u128>(&vector["reward"]),
+                "{name}: height {height}, minted {minted}"
+            );
+        }
+    }
+}
+
+#[test]
+fn supply() {
+    for (name, schedule, case) in schedules() {
+        for vector in case["supply"].as_array().unwrap() {
+            let height = number(&vector["height"]);
+            assert_eq!(
+                schedule.supply_at(height),
+                number::<u128>(&vector["supply"]),
+                "{name}: height {height}"
+            );
+        }
+    }
+}
+
+#[test]
+fn fees() {
+    let vectors: Value = serde_json::from_str(FEES).unwrap();
+    for vector in vectors["fees"].as_array().unwrap() {
+        let amount = number(&vector["amount"]);
+        let basis_points = vector["basisPoints"].as_u64().unwrap() as u32;
+        assert_eq!(
+            fee(amount, basis_points),
+            number::<u128>(&vector["fee"]),
+            "{amount} at {basis_points}bp"
+        );
+    }
+}
+
+#[test]
+fn defaults() {
+    let defaults: Value = serde_json::from_str(DEFAULTS).unwrap();
+    assert_eq!(UNIT, number::<u128>(&defaults["unit"]));
+    assert_eq!(
+        TARGET_BLOCK_TIME_MS,
+        number::<u64>(&defaults["targetBlockTimeMs"])
+    );
+    assert_eq!(
+        u64::from(FEE_BASIS_POINTS),
+        defaults["feeBasisPoints"].as_u64().unwrap()
+    );
+    assert_eq!(REWARD_SCHEDULE, schedule(&defaults));
+
+    // The default schedule is also one of the golden schedules
+    let name = defaults["schedule"].as_str().unwrap();
+    let (_, golden, _) = schedules()
+        .into_iter()
+        .find(|(case, ..)| case == name)
+        .unwrap();
+    assert_eq!(REWARD_SCHEDULE, golden);
+}
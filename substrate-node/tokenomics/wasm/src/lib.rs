@@ -0,0 +1,89 @@
+//! wasm-bindgen build of `coin-tokenomics` for `@coin/tokenomics`.
+//!
+//! Built with `wasm-pack build`; amounts and heights cross as `bigint`.
+
+use coin_tokenomics::RewardSchedule as Schedule;
+use wasm_bindgen::prelude::*;
+
+/// See `coin_tokenomics::RewardSchedule`.
+#[wasm_bindgen]
+pub struct RewardSchedule(Schedule);
+
+#[wasm_bindgen]
+impl RewardSchedule {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        initial_reward: u128,
+        halving_interval: u64,
+        tail_emission: u128,
+        max_supply: u128,
+    ) -> RewardSchedule {
+        RewardSchedule(Schedule {
+            initial_reward,
+            halving_interval,
+            tail_emission,
+            max_supply,
+        })
+    }
+
+    /// The schedule the runtime pays block rewards on.
+    pub fn chain() -> RewardSchedule {
+        RewardSchedule(coin_tokenomics::REWARD_SCHEDULE)
+    }
+
+    #[wasm_bindgen(getter, js_name = initialReward)]
+    pub fn initial_reward(&self) -> u128 {
+        self.0.initial_reward
+    }
+
+    #[wasm_bindgen(getter, js_name = halvingInterval)]
+    pub fn halving_interval(&self) -> u64 {
+        self.0.halving_interval
+    }
+
+    #[wasm_bindgen(getter, js_name = tailEmission)]
+    pub fn tail_emission(&self) -> u128 {
+        self.0.tail_emission
+    }
+
+    #[wasm_bindgen(getter, js_name = maxSupply)]
+    pub fn max_supply(&self) -> u128 {
+        self.0.max_supply
+    }
+
+    /// Reward of the block at `height`, once earlier blocks were paid `minted`.
+    #[wasm_bindgen(js_name = blockReward)]
+    pub fn block_reward(&self, height: u64, minted: u128) -> u128 {
+        self.0.block_reward(height, minted)
+    }
+
+    /// Total paid to the blocks up to and including `height`.
+    #[wasm_bindgen(js_name = supplyAt)]
+    pub fn supply_at(&self, height: u64) -> u128 {
+        self.0.supply_at(height)
+    }
+}
+
+/// One coin in the smallest unit.
+#[wasm_bindgen]
+pub fn unit() -> u128 {
+    coin_tokenomics::UNIT
+}
+
+/// Time the difficulty aims to keep between blocks, in milliseconds.
+#[wasm_bindgen(js_name = targetBlockTimeMs)]
+pub fn target_block_time_ms() -> u64 {
+    coin_tokenomics::TARGET_BLOCK_TIME_MS
+}
+
+/// Fee rate the wallet charges unless told otherwise, in basis points.
+#[wasm_bindgen(js_name = feeBasisPoints)]
+pub fn fee_basis_points() -> u32 {
+    coin_tokenomics::FEE_BASIS_POINTS
+}
+
+/// Fee on `amount` at `basis_points` (hundredths of a percent), rounded down.
+#[wasm_bindgen]
+pub fn fee(amount: u128, basis_points: u32) -> u128 {
+    coin_tokenomics::fee(amount, basis_points)
+}
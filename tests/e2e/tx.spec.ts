@@ -1,7 +1,11 @@
 import { beforeAll, afterAll, describe, it, expect } from 'vitest';
-import { getApi, transfer, getBalance } from '../../lib/substrateApi';
+import { getApi, transfer, getBalance, devAccount, pqAccount } from '../../lib/substrateApi';
 import type { ApiPromise } from '@polkadot/api';
-import { Keyring } from '@polkadot/keyring';
+
+// Needs a dev node funding both accounts and sealing each transaction:
+//   node --dev --sealing instant --miner <hex> \
+//     --endow 0x177c577d91cf59f1008512a5960e280ffc1889d74e10966fa6d6b9d12fbecc3c \
+//     --endow 0x24f6e6fd930a9d10680705972cf647232e1a63d8ce9ad28298b800205185a6b6
 
 let api: ApiPromise;
 
@@ -14,22 +18,20 @@ afterAll(async () => {
 });
 
 describe('Balances Transfer E2E', () => {
-  it('transfers balance between Alice and Bob', async () => {
-    const keyring = new Keyring({ type: 'sr25519' });
-    const alice = keyring.addFromUri('//Alice');
-    const bob = keyring.addFromUri('//Bob');
-    const aliceAddr = alice.address;
-    const bobAddr = bob.address;
-
-    const beforeAlice = await getBalance(aliceAddr);
-    const beforeBob = await getBalance(bobAddr);
+  it('transfers balance between two ML-DSA accounts', async () => {
+    const alice = await devAccount();
+    const bob = await pqAccount(new Uint8Array(32).fill(2));
+
+    const beforeAlice = await getBalance(alice.address);
+    const beforeBob = await getBalance(bob.address);
     const amount = 1n;
-    await transfer(bobAddr, amount, 0n, alice);
+    await transfer(bob.address, amount, 0n, alice);
 
-    const afterAlice = await getBalance(aliceAddr);
-    const afterBob = await getBalance(bobAddr);
+    const afterAlice = await getBalance(alice.address);
+    const afterBob = await getBalance(bob.address);
 
-    expect(afterAlice).toEqual(beforeAlice - amount);
+    // Alice pays the fee, too
+    expect(afterAlice < beforeAlice - amount).toBe(true);
     expect(afterBob).toEqual(beforeBob + amount);
   });
-});
\ No newline at end of file
+});
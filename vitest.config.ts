@@ -5,7 +5,10 @@ export default defineConfig({
   resolve: {
     alias: {
       // Alias wallet package
-      '@coin/wallet': path.resolve(__dirname, 'packages/wallet/src')
+      '@coin/wallet': path.resolve(__dirname, 'packages/wallet/src'),
+      '@coin/tokenomics': path.resolve(__dirname, 'packages/tokenomics/src'),
+      // Node build of the wasm tokenomics: `yarn workspace coin-tokenomics build:node`
+      'coin-tokenomics': path.resolve(__dirname, 'substrate-node/tokenomics/pkg-node/coin_tokenomics_wasm.js')
     }
   },
   test: {